@@ -1,9 +1,13 @@
 use std::fs::File;
-use clap::Parser;
+use std::io::{self, BufRead, Write};
+use clap::{Parser, Subcommand, ValueEnum};
 use log::info;
 use simplelog::*;
 
-use draughtlib::{Game, Team};
+use draughtlib::{Game, GameResult, GameStatus, PdnMove, Rules, Team, Variant};
+
+mod tournament;
+use tournament::{Engine, Settings};
 
 /// Command-line arguments for configuring the server
 #[derive(Parser, Debug)]
@@ -23,6 +27,61 @@ struct Args {
 
     #[arg(short, long, default_value_t = 4)]
     search: usize,
+
+    /// Who plays Black
+    #[arg(long, value_enum, default_value_t = Player::Human)]
+    black: Player,
+
+    /// Who plays White
+    #[arg(long, value_enum, default_value_t = Player::Ai)]
+    white: Player,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+/// Modes other than playing a game in the terminal
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Play AI against AI many times and report the Elo difference between them
+    Tournament {
+        /// First engine, settings written as depth=4,chance=1,weights=balanced,time=0
+        #[arg(long, default_value = "")]
+        first: Engine,
+
+        /// Second engine, scored against the first
+        #[arg(long, default_value = "")]
+        second: Engine,
+
+        /// Number of openings, each is played once with each engine as Black
+        #[arg(short, long, default_value_t = 50)]
+        openings: usize,
+
+        /// Random moves from the start making up each opening
+        #[arg(long, default_value_t = 4)]
+        opening_plies: usize,
+
+        /// Plies after which a game is adjudicated a draw
+        #[arg(long, default_value_t = 200)]
+        max_plies: usize,
+
+        /// Games played at once
+        #[arg(short, long, default_value_t = 4)]
+        threads: usize,
+
+        /// Seed for the openings and the engines' random choices, so a tournament can be played again
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+}
+
+/// Who makes the moves for a team
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum Player {
+    /// Moves are typed in numeric notation
+    Human,
+    /// Moves are searched for by the computer
+    Ai,
 }
 
 fn main() {
@@ -39,7 +98,127 @@ fn main() {
     info!("       draught");
     info!("======================");
 
-    let game = Game::new(args.width, args.height, args.piece_rows, Team::White, args.search);
+    if let Some(Command::Tournament { first, second, openings, opening_plies, max_plies, threads, seed }) = args.command {
+        let rules = Rules::sized(args.width, args.height, args.piece_rows);
+        let settings = Settings {
+            rules,
+            first_turn: first_turn(&rules),
+            openings,
+            opening_plies,
+            max_plies,
+            threads,
+            seed,
+        };
+
+        info!("{} vs {}", first, second);
+
+        let score = tournament::run(&settings, &first, &second, |score| {
+            print!("\rPlayed {}/{}", score.games(), openings * 2);
+            io::stdout().flush().unwrap();
+        });
+
+        println!();
+        println!("{}", first);
+        println!("vs {}", second);
+        println!("{}", score);
+        return;
+    }
+
+    let rules = Rules::sized(args.width, args.height, args.piece_rows);
+    let mut game = Game::with_rules(rules, first_turn(&rules), args.search);
+
+    println!("Enter moves in numeric notation, 11-15 or 22x15, or quit to stop");
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+
+    loop {
+        println!();
+        println!("{}", game.current_board());
+
+        if let Some(result) = game.result() {
+            println!("{}", result_text(result, game.status()));
+            break;
+        }
+
+        let team = game.current_turn();
+        let player = match team {
+            Team::Black => args.black,
+            Team::White => args.white,
+        };
+
+        match player {
+            Player::Ai => {
+                let before = game.current_board().clone();
+                if let Err(e) = game.ai_move() {
+                    println!("{}", e);
+                    break;
+                }
+
+                let played = before.legal_moves()
+                    .into_iter()
+                    .find(|mv| mv.apply(&before) == *game.current_board())
+                    .and_then(|mv| PdnMove::from_move(&before, &mv));
+
+                match played {
+                    Some(mv) => println!("{} plays {}", team_name(team), mv),
+                    None => {
+                        println!("{} couldn't find a move", team_name(team));
+                        break;
+                    },
+                }
+            },
+            Player::Human => {
+                println!("{} to move: {}", team_name(team), game.legal_notation().join(", "));
+                print!("> ");
+                io::stdout().flush().unwrap();
+
+                let line = match lines.next() {
+                    Some(Ok(line)) => line,
+                    _ => break,
+                };
+
+                match line.trim() {
+                    "" => continue,
+                    "quit" | "exit" => break,
+                    notation => {
+                        if let Err(e) = game.play_notation(notation) {
+                            println!("{}", e);
+                        }
+                    },
+                }
+            },
+        }
+    }
+}
+
+/// Team that moves first under the given rules, Black unless they're a variant where White starts
+fn first_turn(rules: &Rules) -> Team {
+    Variant::from_rules(rules).map_or(Team::Black, |variant| variant.first_turn())
+}
+
+/// Full name of a team
+fn team_name(team: Team) -> &'static str {
+    match team {
+        Team::Black => "Black",
+        Team::White => "White",
+    }
+}
+
+/// Describe how a finished game ended
+fn result_text(result: GameResult, status: GameStatus) -> String {
+    let reason = match status {
+        GameStatus::InProgress => "",
+        GameStatus::NoPieces => ", no pieces left",
+        GameStatus::NoMoves => ", no moves left",
+        GameStatus::Repetition => " by repetition",
+        GameStatus::MoveLimit => " by the move limit",
+        GameStatus::AgreedDraw => " by agreement",
+    };
 
-    println!("{}", game.current_board())
+    match result {
+        GameResult::BlackWin => format!("Black wins{}", reason),
+        GameResult::WhiteWin => format!("White wins{}", reason),
+        GameResult::Draw => format!("Draw{}", reason),
+    }
 }
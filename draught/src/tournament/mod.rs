@@ -0,0 +1,305 @@
+//! Batches of AI vs AI games between two engine configurations, scored with an Elo difference
+//!
+//! Each opening is a few random moves from the start and is played twice with the engines swapping colours, so neither
+//! engine gains from a lopsided opening. Games are spread over threads and adjudicated as draws after a number of plies
+
+use std::fmt::{Display};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+use draughtlib::{Board, Game, GameResult, Rules, Team, Weights};
+
+#[cfg(test)] mod tests;
+
+/// Settings for one side of a tournament, written as `depth=4,chance=0.9,weights=balanced,time=100`
+///
+/// Weights other than the presets are written as their terms split by slashes, `weights=100/200/8/15/5/2/30`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Engine {
+    /// Moves searched ahead
+    pub depth: usize,
+    /// Chance of searching for a move instead of playing at random
+    pub chance: f64,
+    /// Weights to score boards with, piece count only when [`None`]
+    pub weights: Option<Weights>,
+    /// Milliseconds to search for each move instead of a fixed depth, 0 to use the depth
+    pub time: f64,
+}
+
+impl Default for Engine {
+    fn default() -> Engine {
+        Engine {
+            depth: 4,
+            chance: 1.0,
+            weights: None,
+            time: 0.,
+        }
+    }
+}
+
+impl Engine {
+    /// Set a game up so that its AI plays as this engine
+    fn configure(&self, game: &mut Game) {
+        game.set_search_depth(self.depth);
+        game.set_perfect_chance(self.chance);
+        game.set_search_time(self.time);
+
+        match self.weights {
+            Some(weights) => game.set_weights(weights),
+            None => game.clear_weights(),
+        }
+    }
+}
+
+impl FromStr for Engine {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Engine, String> {
+        let mut engine = Engine::default();
+
+        for setting in s.split(',').map(str::trim).filter(|setting| !setting.is_empty()) {
+            let (name, value) = setting.split_once('=')
+                .ok_or_else(|| format!("Expected name=value, got {}", setting))?;
+            let invalid = || format!("Invalid value for {}: {}", name, value);
+
+            match name {
+                "depth" => engine.depth = value.parse().map_err(|_| invalid())?,
+                "chance" => engine.chance = value.parse().map_err(|_| invalid())?,
+                "time" => engine.time = value.parse().map_err(|_| invalid())?,
+                "weights" => engine.weights = match value {
+                    "material" => None,
+                    "balanced" => Some(Weights::balanced()),
+                    "aggressive" => Some(Weights::aggressive()),
+                    "defensive" => Some(Weights::defensive()),
+                    _ => Some(parse_weights(value).ok_or_else(invalid)?),
+                },
+                _ => return Err(format!("Unknown engine setting: {}", name)),
+            }
+        }
+
+        Ok(engine)
+    }
+}
+
+/// Read weights written as `man/king/advancement/back_rank/centre/mobility/runaway`
+fn parse_weights(value: &str) -> Option<Weights> {
+    let terms = value.split('/')
+        .map(|term| term.parse().ok())
+        .collect::<Option<Vec<isize>>>()?;
+
+    match terms[..] {
+        [man, king, advancement, back_rank, centre, mobility, runaway] => Some(Weights::new(man, king, advancement, back_rank, centre, mobility, runaway)),
+        _ => None,
+    }
+}
+
+impl Display for Engine {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let weights = match self.weights {
+            None => "material".to_string(),
+            Some(w) if w == Weights::balanced() => "balanced".to_string(),
+            Some(w) if w == Weights::aggressive() => "aggressive".to_string(),
+            Some(w) if w == Weights::defensive() => "defensive".to_string(),
+            Some(w) => format!("{}/{}/{}/{}/{}/{}/{}", w.man, w.king, w.advancement, w.back_rank, w.centre, w.mobility, w.runaway),
+        };
+
+        write!(f, "depth={},chance={},weights={}", self.depth, self.chance, weights)?;
+        if self.time > 0. {
+            write!(f, ",time={}", self.time)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// How the games of a tournament are played
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Settings {
+    pub rules: Rules,
+    pub first_turn: Team,
+    /// Number of openings, each is played twice
+    pub openings: usize,
+    /// Random moves from the start making up each opening
+    pub opening_plies: usize,
+    /// Plies after which a game still being played is adjudicated a draw
+    pub max_plies: usize,
+    pub threads: usize,
+    /// Seed for the openings and each game's AI, the same seed plays the same games whatever the number of threads
+    pub seed: Option<u64>,
+}
+
+/// Wins, draws and losses from the first engine's point of view
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Score {
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+}
+
+impl Score {
+    pub fn games(&self) -> usize {
+        self.wins + self.draws + self.losses
+    }
+
+    /// Add a game's result
+    pub fn record(&mut self, result: Option<GameResult>, first_engine: Team) {
+        match result {
+            Some(GameResult::BlackWin) if first_engine == Team::Black => self.wins += 1,
+            Some(GameResult::WhiteWin) if first_engine == Team::White => self.wins += 1,
+            Some(GameResult::BlackWin) | Some(GameResult::WhiteWin) => self.losses += 1,
+            Some(GameResult::Draw) | None => self.draws += 1,
+        }
+    }
+
+    /// Points per game, a win is 1 and a draw is half
+    pub fn ratio(&self) -> f64 {
+        (self.wins as f64 + self.draws as f64 / 2.) / self.games() as f64
+    }
+
+    /// Elo difference between the engines and the half-width of its 95% confidence interval
+    ///
+    /// The interval comes from the spread of the per-game scores, [`None`] when there are no games. Either can be
+    /// infinite when one engine won or lost every game
+    pub fn elo(&self) -> Option<(f64, f64)> {
+        if self.games() == 0 {
+            return None;
+        }
+
+        let n = self.games() as f64;
+        let ratio = self.ratio();
+
+        let variance = [(self.wins, 1.), (self.draws, 0.5), (self.losses, 0.)]
+            .iter()
+            .map(|(count, points)| *count as f64 * (points - ratio).powi(2))
+            .sum::<f64>() / n;
+        let margin = 1.96 * (variance / n).sqrt();
+
+        let low = elo_difference((ratio - margin).max(0.));
+        let high = elo_difference((ratio + margin).min(1.));
+
+        let elo = elo_difference(ratio);
+        match elo.is_finite() {
+            true => Some((elo, (high - low) / 2.)),
+            false => Some((elo, f64::INFINITY)),
+        }
+    }
+}
+
+impl Display for Score {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "{:>6} {:>6} {:>6} {:>6} {:>7}", "Games", "Wins", "Draws", "Losses", "Score")?;
+        writeln!(f, "{:>6} {:>6} {:>6} {:>6} {:>6.1}%", self.games(), self.wins, self.draws, self.losses, self.ratio() * 100.)?;
+
+        if let Some((elo, margin)) = self.elo() {
+            write!(f, "Elo difference: {:+.1} ± {:.1}", elo, margin)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Elo difference expected to give the ratio of points per game
+pub fn elo_difference(ratio: f64) -> f64 {
+    -400. * (1. / ratio - 1.).log10()
+}
+
+/// Starting board for each opening, random legal moves from the start
+fn openings<R: Rng>(settings: &Settings, rng: &mut R) -> Vec<Board> {
+    (0..settings.openings)
+        .map(|_| {
+            let mut board = Game::with_rules(settings.rules, settings.first_turn, 0).current_board().clone();
+
+            for _ in 0..settings.opening_plies {
+                match board.legal_moves().choose(rng) {
+                    Some(mv) => board = mv.apply(&board),
+                    None => break,
+                }
+            }
+
+            board
+        })
+        .collect()
+}
+
+/// Play a game from an opening with each team's engine, [`None`] if it was adjudicated
+fn play_game(settings: &Settings, number: usize, opening: &Board, black: &Engine, white: &Engine) -> Option<GameResult> {
+    let mut game = Game::with_rules(settings.rules, settings.first_turn, 0);
+    game.set_current(opening.clone());
+
+    if let Some(seed) = settings.seed {
+        game.set_seed(seed.wrapping_add(number as u64));
+    }
+
+    for _ in 0..settings.max_plies {
+        if let Some(result) = game.result() {
+            return Some(result);
+        }
+
+        match game.current_turn() {
+            Team::Black => black.configure(&mut game),
+            Team::White => white.configure(&mut game),
+        }
+
+        let ply = game.ply();
+
+        // no move found, treat as finished
+        if game.ai_move().is_err() || game.ply() == ply {
+            break;
+        }
+    }
+
+    game.result()
+}
+
+/// Play every game of a tournament between two engines, calling `progress` with the score so far after each game
+pub fn run<F: FnMut(&Score)>(settings: &Settings, first: &Engine, second: &Engine, mut progress: F) -> Score {
+    let mut rng = match settings.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let openings = openings(settings, &mut rng);
+    let next_game = AtomicUsize::new(0);
+    let total = openings.len() * 2;
+    let (sender, receiver) = mpsc::channel();
+
+    let mut score = Score::default();
+
+    thread::scope(|scope| {
+        for _ in 0..settings.threads.max(1) {
+            let sender = sender.clone();
+            let (openings, next_game) = (&openings, &next_game);
+
+            scope.spawn(move || loop {
+                let game = next_game.fetch_add(1, Ordering::Relaxed);
+                if game >= total {
+                    break;
+                }
+
+                // the first engine plays each opening as both colours
+                let first_engine = if game & 1 == 0 { Team::Black } else { Team::White };
+                let result = match first_engine {
+                    Team::Black => play_game(settings, game, &openings[game / 2], first, second),
+                    Team::White => play_game(settings, game, &openings[game / 2], second, first),
+                };
+
+                if sender.send((result, first_engine)).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        for (result, first_engine) in receiver {
+            score.record(result, first_engine);
+            progress(&score);
+        }
+    });
+
+    score
+}
@@ -0,0 +1,97 @@
+use super::*;
+
+#[test]
+fn parse_engine() {
+    assert_eq!("".parse::<Engine>(), Ok(Engine::default()));
+
+    let engine: Engine = "depth=6, chance=0.8,weights=defensive".parse().unwrap();
+    assert_eq!(engine, Engine { depth: 6, chance: 0.8, weights: Some(Weights::defensive()), time: 0. });
+    assert_eq!(engine.to_string(), "depth=6,chance=0.8,weights=defensive");
+    assert_eq!(engine.to_string().parse::<Engine>(), Ok(engine));
+
+    assert!("depth".parse::<Engine>().is_err());
+    assert!("depth=deep".parse::<Engine>().is_err());
+    assert!("speed=2".parse::<Engine>().is_err());
+    assert!("weights=heavy".parse::<Engine>().is_err());
+    assert!("weights=1/2/3".parse::<Engine>().is_err());
+}
+
+#[test]
+fn parse_engine_custom_weights() {
+    let engine: Engine = "depth=3,weights=100/250/5/10/0/1/20".parse().unwrap();
+    assert_eq!(engine.weights, Some(Weights::new(100, 250, 5, 10, 0, 1, 20)));
+
+    // labels printed for custom weights can be passed back in
+    assert_eq!(engine.to_string(), "depth=3,chance=1,weights=100/250/5/10/0/1/20");
+    assert_eq!(engine.to_string().parse::<Engine>(), Ok(engine));
+}
+
+#[test]
+fn score_record() {
+    let mut score = Score::default();
+    score.record(Some(GameResult::BlackWin), Team::Black);
+    score.record(Some(GameResult::BlackWin), Team::White);
+    score.record(Some(GameResult::WhiteWin), Team::White);
+    score.record(Some(GameResult::Draw), Team::Black);
+    score.record(None, Team::White);
+
+    assert_eq!(score, Score { wins: 2, draws: 2, losses: 1 });
+    assert_eq!(score.games(), 5);
+    assert_eq!(score.ratio(), 0.6);
+}
+
+#[test]
+fn score_elo() {
+    assert_eq!(Score::default().elo(), None);
+    assert_eq!(Score { wins: 5, draws: 10, losses: 5 }.elo().unwrap().0, 0.);
+
+    // 75% is about 191 Elo
+    let (elo, margin) = Score { wins: 60, draws: 30, losses: 10 }.elo().unwrap();
+    assert!((elo - 190.85).abs() < 0.01);
+    assert!(margin > 0. && margin < 100.);
+
+    // more games narrow the interval
+    let (_, wider) = Score { wins: 6, draws: 3, losses: 1 }.elo().unwrap();
+    assert!(wider > margin);
+
+    let (elo, margin) = Score { wins: 3, draws: 0, losses: 0 }.elo().unwrap();
+    assert!(elo.is_infinite() && margin.is_infinite());
+}
+
+#[test]
+fn run_tournament() {
+    let settings = Settings {
+        rules: Rules::default(),
+        first_turn: Team::Black,
+        openings: 2,
+        opening_plies: 2,
+        max_plies: 20,
+        threads: 2,
+        seed: None,
+    };
+    let engine = Engine { depth: 1, ..Engine::default() };
+
+    let mut updates = 0;
+    let score = run(&settings, &engine, &engine, |_| updates += 1);
+
+    assert_eq!(score.games(), 4);
+    assert_eq!(updates, 4);
+}
+
+#[test]
+fn seeded_tournament() {
+    let settings = |threads| Settings {
+        rules: Rules::default(),
+        first_turn: Team::Black,
+        openings: 3,
+        opening_plies: 2,
+        max_plies: 30,
+        threads,
+        seed: Some(12),
+    };
+    let first = Engine { depth: 2, chance: 0.7, ..Engine::default() };
+    let second = Engine { depth: 1, chance: 0.7, ..Engine::default() };
+
+    let score = run(&settings(1), &first, &second, |_| {});
+    assert_eq!(run(&settings(3), &first, &second, |_| {}), score);
+}
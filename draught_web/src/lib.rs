@@ -9,7 +9,7 @@ extern crate wasm_bindgen;
 use wasm_bindgen::prelude::*;
 
 pub use draughtlib::{Board, Game, Computer};
-pub use paint::Painter;
+pub use paint::{Painter, Highlight};
 
 /// Wrap the [`web_sys`] access to the browser console in a macro for easy logging
 #[macro_export]
@@ -7,10 +7,14 @@ use wasm_bindgen::{JsCast, JsValue};
 use web_sys::HtmlCanvasElement;
 use web_sys::CanvasRenderingContext2d;
 
+use js_sys::{Function, Promise};
+
 use std::f64;
+use std::rc::Rc;
+use std::cell::{Cell, RefCell};
 
 use crate::log;
-use draughtlib::{Board, BrdIdx, PieceIterator, Game};
+use draughtlib::{Board, BrdIdx, PieceIterator, Game, Move, Piece};
 
 use draughtlib::Team::*;
 use draughtlib::Strength::*;
@@ -50,13 +54,55 @@ const PIECE_OUTLINE_PROPORTION: f64 = 0.25;
 /// Proportion of square that piece fills as proportion of min cell dimension
 const PIECE_PROPORTION: f64 = 0.6;
 
+/// Default hex colour value for squares the selected piece can move to
+const DESTINATION: &str = "#53d45b";
+/// Default hex colour value for outlining pieces that have to capture
+const FORCED_CAPTURE: &str = "#ff8c00";
+/// Default hex colour value for the square the last move started from
+const LAST_FROM: &str = "#3a4a63";
+/// Default hex colour value for the square the last move finished on
+const LAST_TO: &str = "#4d6a99";
+/// Default hex colour value for marking pieces about to be captured
+const CAPTURED: &str = "#00e5ff";
+/// Radius of the marker on squares the selected piece can move to as proportion of piece radius
+const DESTINATION_PROPORTION: f64 = 0.35;
+
+/// Default milliseconds for a piece to slide along its move
+const ANIMATION_DURATION: f64 = 400.0;
+/// Length of the flash when a piece is crowned as proportion of the animation duration
+const PROMOTION_FLASH_PROPORTION: f64 = 0.5;
+/// Default hex colour value for the flash when a piece is crowned
+const PROMOTION_FLASH: &str = "#ffea00";
+/// Radius that the promotion flash grows to as proportion of piece radius
+const PROMOTION_FLASH_SIZE: f64 = 1.6;
+
+/// Callback run by `requestAnimationFrame` with the time of the frame
+type Frame = Closure<dyn FnMut(f64)>;
+
+/// Kinds of square highlighting, each drawn in its own colour
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Highlight {
+    /// Square the selected piece can move to
+    Destination = 0,
+    /// Piece that has to take, capturing is compulsory
+    ForcedCapture = 1,
+    /// Square the last move started from
+    LastFrom = 2,
+    /// Square the last move finished on
+    LastTo = 3,
+    /// Piece taken by one of the selected piece's moves or by the multi-jump in progress
+    Captured = 4,
+}
+
 /// Used to paint boards onto HTML canvases
 #[wasm_bindgen]
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Painter {
     canvas: HtmlCanvasElement,
     context: CanvasRenderingContext2d,
     selected_idx: Option<BrdIdx>,
+    highlights: Vec<(BrdIdx, Highlight)>,
 
     white_square: JsValue,
     black_square: JsValue,
@@ -70,6 +116,12 @@ pub struct Painter {
     selected_piece_line: JsValue,
     king_line: JsValue,
 
+    destination: String,
+    forced_capture: String,
+    last_from: String,
+    last_to: String,
+    captured: String,
+
     piece_lines: bool,
     piece_line_proportion: f64,
 
@@ -77,6 +129,11 @@ pub struct Painter {
     outline_width: f64,
     draw_outline: bool,
 
+    /// Milliseconds for a piece to slide along its move when animating
+    animation_duration: f64,
+    /// Counts animations started, shared with the frames of each so that a newer animation or a cancel stops older ones
+    animation: Rc<Cell<usize>>,
+
     width: u32,
     height: u32,
 }
@@ -128,6 +185,243 @@ impl Painter {
 
         context
     }
+
+    /// Highlight a square unless it already has the same highlight
+    fn push_highlight(&mut self, idx: BrdIdx, highlight: Highlight) {
+        if !self.highlights.contains(&(idx, highlight)) {
+            self.highlights.push((idx, highlight));
+        }
+    }
+
+    /// Colour value for a kind of highlight
+    fn highlight_colour(&self, highlight: Highlight) -> &str {
+        match highlight {
+            Highlight::Destination => &self.destination,
+            Highlight::ForcedCapture => &self.forced_capture,
+            Highlight::LastFrom => &self.last_from,
+            Highlight::LastTo => &self.last_to,
+            Highlight::Captured => &self.captured,
+        }
+    }
+
+    /// Highlighted squares of a kind
+    fn highlighted(&self, highlight: Highlight) -> impl Iterator<Item = BrdIdx> + '_ {
+        self.highlights
+            .iter()
+            .filter(move |(_, h)| *h == highlight)
+            .map(|(idx, _)| *idx)
+    }
+
+    /// Fill the background and draw the playable squares of a board
+    fn draw_squares(&self, board: &Board, cell_width: usize, cell_height: usize, square_outline: f64) {
+        self.context.set_fill_style(&self.white_square);
+        self.context.fill_rect(0.0, 0.0, self.width as f64, self.height as f64);
+
+        self.context.set_fill_style(&self.black_square);
+        self.context.set_stroke_style(&self.square_outline);
+        self.context.set_line_width(square_outline);
+
+        // Draw black squares onto canvas
+        for i in 0..board.height {
+            for j in 0..board.width {
+
+                if i % 2 == 0 {
+                    if j % 2 == 1 {
+                        self.context.fill_rect(
+                            (j * cell_width) as f64, 
+                            (i * cell_height) as f64, 
+                            cell_width as f64, 
+                            cell_height as f64
+                        );
+
+                        if self.draw_outline {
+                            self.context.stroke_rect(
+                                (j * cell_width) as f64, 
+                                (i * cell_height) as f64, 
+                                cell_width as f64, 
+                                cell_height as f64
+                            );
+                        }
+                    }
+                }
+                else {
+                    if j % 2 == 0 {
+                        self.context.fill_rect(
+                            (j * cell_width) as f64, 
+                            (i * cell_height) as f64, 
+                            cell_width as f64, 
+                            cell_height as f64
+                        );
+
+                        if self.draw_outline {
+                            self.context.stroke_rect(
+                                (j * cell_width) as f64, 
+                                (i * cell_height) as f64, 
+                                cell_width as f64, 
+                                cell_height as f64
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Draw a piece centred on the given canvas coordinates
+    fn draw_piece(&self, piece: Piece, center_x: f64, center_y: f64, cell_radius: f64, piece_outline: f64) {
+        match piece.team {
+            Black => {
+                self.context.set_fill_style(&self.black_piece);
+                self.context.set_stroke_style(&self.black_piece_line);
+            },
+            White => {
+                self.context.set_fill_style(&self.white_piece);
+                self.context.set_stroke_style(&self.white_piece_line);
+            },
+        }
+
+        if piece.strength == King {
+            self.context.set_stroke_style(&self.king_line);
+        }
+
+        self.context.begin_path();
+        match self.context.arc(
+            center_x, 
+            center_y, 
+            cell_radius, // radius 
+            0.0, // start angle
+            f64::consts::PI * 2.0) // end angle
+        {
+            Ok(res) => res,
+            Err(err) => log!("Failed to draw piece at ({}, {}), {:?}", center_x, center_y, err),
+        };
+        self.context.fill();
+
+        if self.piece_lines {
+            self.context.set_line_width(piece_outline);
+            self.context.stroke()
+        }
+    }
+
+    /// Canvas coordinates of the centre of a square
+    fn cell_centre(idx: BrdIdx, cell_width: usize, cell_height: usize) -> (f64, f64) {
+        (
+            (idx.col as f64 * cell_width as f64) + (cell_width as f64) / 2.0,
+            (idx.row as f64 * cell_height as f64) + (cell_height as f64) / 2.0,
+        )
+    }
+
+    /// Draw a frame of a move part-way along its path, from 0 at the start to 1 at the end
+    /// 
+    /// Each captured piece fades out while the moving piece makes the hop over it
+    fn draw_slide(&self, board: &Board, mv: &Move, progress: f64) {
+        let cell_height = self.height as usize / board.height;
+        let cell_width = self.width as usize / board.width;
+
+        let min_dimension = usize::min(cell_width, cell_height) as f64;
+
+        let cell_radius = min_dimension * PIECE_PROPORTION / 2.0;
+
+        let piece_outline = cell_radius * self.piece_line_proportion;
+        let square_outline = min_dimension * self.outline_width;
+
+        self.draw_squares(board, cell_width, cell_height, square_outline);
+
+        let mut path = vec![mv.from()];
+        match mv.hops() {
+            [] => path.push(mv.to()),
+            hops => path.extend_from_slice(hops),
+        }
+
+        let segments = path.len() - 1;
+        let travelled = progress.clamp(0.0, 1.0) * segments as f64;
+        let segment = usize::min(travelled.floor() as usize, segments - 1);
+        let along = travelled - segment as f64;
+
+        let captured = mv.captured();
+
+        for (idx, square) in PieceIterator::new(board) {
+            let brd_idx = board.board_index(idx);
+
+            let piece = match square.occupant {
+                Some(piece) if brd_idx != mv.from() => piece,
+                _ => continue,
+            };
+
+            // captured pieces are jumped in order, one per hop
+            let alpha = match captured.iter().position(|c| *c == brd_idx) {
+                Some(hop) if hop < segment => 0.0,
+                Some(hop) if hop == segment => 1.0 - along,
+                _ => 1.0,
+            };
+
+            let (center_x, center_y) = Painter::cell_centre(brd_idx, cell_width, cell_height);
+
+            self.context.set_global_alpha(alpha);
+            self.draw_piece(piece, center_x, center_y, cell_radius, piece_outline);
+        }
+        self.context.set_global_alpha(1.0);
+
+        if let Some(piece) = board.cell(board.cell_idx(mv.from())).occupant {
+            let (start_x, start_y) = Painter::cell_centre(path[segment], cell_width, cell_height);
+            let (end_x, end_y) = Painter::cell_centre(path[segment + 1], cell_width, cell_height);
+
+            self.draw_piece(
+                piece,
+                start_x + (end_x - start_x) * along,
+                start_y + (end_y - start_y) * along,
+                cell_radius,
+                piece_outline
+            );
+        }
+    }
+
+    /// Draw a frame of a newly crowned piece flashing, from 0 at the start to 1 at the end
+    fn draw_flash(&self, board: &Board, idx: BrdIdx, progress: f64) {
+        self.draw(board);
+
+        let cell_height = self.height as usize / board.height;
+        let cell_width = self.width as usize / board.width;
+
+        let min_dimension = usize::min(cell_width, cell_height) as f64;
+
+        let cell_radius = min_dimension * PIECE_PROPORTION / 2.0;
+        let piece_outline = cell_radius * self.piece_line_proportion;
+
+        let progress = progress.clamp(0.0, 1.0);
+        let (center_x, center_y) = Painter::cell_centre(idx, cell_width, cell_height);
+
+        self.context.set_global_alpha(1.0 - progress);
+        self.context.set_stroke_style_str(PROMOTION_FLASH);
+        self.context.set_line_width(piece_outline);
+
+        self.context.begin_path();
+        match self.context.arc(
+            center_x, 
+            center_y, 
+            cell_radius * (1.0 + (PROMOTION_FLASH_SIZE - 1.0) * progress), 
+            0.0, 
+            f64::consts::PI * 2.0)
+        {
+            Ok(res) => res,
+            Err(err) => log!("Failed to paint promotion, idx: {}, {:?}", idx, err),
+        };
+        self.context.stroke();
+
+        self.context.set_global_alpha(1.0);
+    }
+
+    /// Schedule a closure for the browser's next repaint
+    fn request_animation_frame(frame: &Frame) {
+        match web_sys::window() {
+            Some(window) => {
+                if let Err(err) = window.request_animation_frame(frame.as_ref().unchecked_ref()) {
+                    log!("Failed to request animation frame, {:?}", err);
+                }
+            },
+            None => log!("No Js window returned when requesting animation frame"),
+        }
+    }
 }
 
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
@@ -149,6 +443,7 @@ impl Painter {
             context,
             width, height,
             selected_idx: None,
+            highlights: Vec::new(),
 
             white_square: JsValue::from_str(WHITE_SQUARE),
             black_square: JsValue::from_str(BLACK_SQUARE),
@@ -161,12 +456,20 @@ impl Painter {
             black_piece_line: JsValue::from_str(BLACK_PIECE_OUTLINE),
             selected_piece_line: JsValue::from_str(SELECTED_PIECE_OUTLINE),
             king_line: JsValue::from_str(KING_OUTLINE),
+            destination: DESTINATION.to_string(),
+            forced_capture: FORCED_CAPTURE.to_string(),
+            last_from: LAST_FROM.to_string(),
+            last_to: LAST_TO.to_string(),
+            captured: CAPTURED.to_string(),
             piece_lines: DRAW_PIECE_OUTLINES,
             piece_line_proportion: PIECE_OUTLINE_PROPORTION,
 
             square_outline: JsValue::from_str(SQUARE_OUTLINE),
             outline_width: OUTLINE_WIDTH,
             draw_outline: DRAW_OUTLINE,
+
+            animation_duration: ANIMATION_DURATION,
+            animation: Rc::new(Cell::new(0)),
         }
     }
 
@@ -182,6 +485,7 @@ impl Painter {
             context,
             width, height,
             selected_idx: None,
+            highlights: Vec::new(),
 
             white_square: JsValue::from_str(WHITE_SQUARE),
             black_square: JsValue::from_str(BLACK_SQUARE),
@@ -194,12 +498,20 @@ impl Painter {
             black_piece_line: JsValue::from_str(BLACK_PIECE_OUTLINE),
             selected_piece_line: JsValue::from_str(SELECTED_PIECE_OUTLINE),
             king_line: JsValue::from_str(KING_OUTLINE),
+            destination: DESTINATION.to_string(),
+            forced_capture: FORCED_CAPTURE.to_string(),
+            last_from: LAST_FROM.to_string(),
+            last_to: LAST_TO.to_string(),
+            captured: CAPTURED.to_string(),
             piece_lines: DRAW_PIECE_OUTLINES,
             piece_line_proportion: PIECE_OUTLINE_PROPORTION,
 
             square_outline: JsValue::from_str(SQUARE_OUTLINE),
             outline_width: OUTLINE_WIDTH,
             draw_outline: DRAW_OUTLINE,
+
+            animation_duration: ANIMATION_DURATION,
+            animation: Rc::new(Cell::new(0)),
         }
     }
 
@@ -213,6 +525,66 @@ impl Painter {
         self.selected_idx = None;
     }
 
+    /// Highlight a square, a square can have more than one kind of highlight
+    pub fn add_highlight(&mut self, idx: &BrdIdx, highlight: Highlight) {
+        self.push_highlight(*idx, highlight);
+    }
+
+    /// Remove every highlight
+    pub fn clear_highlights(&mut self) {
+        self.highlights.clear();
+    }
+
+    /// Set new colour value for a kind of highlight
+    pub fn set_highlight_colour(&mut self, highlight: Highlight, value: String) {
+        match highlight {
+            Highlight::Destination => self.destination = value,
+            Highlight::ForcedCapture => self.forced_capture = value,
+            Highlight::LastFrom => self.last_from = value,
+            Highlight::LastTo => self.last_to = value,
+            Highlight::Captured => self.captured = value,
+        }
+    }
+
+    /// Replace the highlights with those from a game's legal moves
+    /// 
+    /// The last move is always highlighted. With a piece selected, its destinations and the pieces it would take are
+    /// highlighted, otherwise the pieces that have to take when a capture is compulsory
+    pub fn highlight_moves(&mut self, game: &Game) {
+        self.highlights.clear();
+
+        if let Some(mv) = game.last_move() {
+            self.push_highlight(mv.from(), Highlight::LastFrom);
+            self.push_highlight(mv.to(), Highlight::LastTo);
+
+            // jumped pieces stay on the board until the multi-jump is finished
+            if game.jumping_piece().is_some() {
+                for idx in mv.captured() {
+                    self.push_highlight(idx, Highlight::Captured);
+                }
+            }
+        }
+
+        match self.selected_idx {
+            Some(selected) => {
+                for mv in game.legal_moves_from(&selected) {
+                    // jumps are made a hop at a time
+                    let destination = mv.hops().first().copied().unwrap_or(mv.to());
+                    self.push_highlight(destination, Highlight::Destination);
+
+                    for idx in mv.captured() {
+                        self.push_highlight(idx, Highlight::Captured);
+                    }
+                }
+            },
+            None => {
+                for mv in game.legal_moves().iter().filter(|mv| mv.is_capture()) {
+                    self.push_highlight(mv.from(), Highlight::ForcedCapture);
+                }
+            },
+        }
+    }
+
     /// Set new square outline colour value
     pub fn set_square_outline(&mut self, value: JsValue) {
         self.square_outline = value;
@@ -228,6 +600,92 @@ impl Painter {
         self.draw_outline = value;
     }
 
+    /// Set milliseconds for a piece to slide along its move when animating, a crowned piece flashes for half as long again
+    pub fn set_animation_duration(&mut self, value: f64) {
+        self.animation_duration = value.max(0.0);
+    }
+
+    /// Stop drawing any animation in progress, its promise resolves at the next frame
+    pub fn cancel_animation(&self) {
+        self.animation.set(self.animation.get() + 1);
+    }
+
+    /// Animate a move from the given board with `requestAnimationFrame`, the returned promise resolves once it's finished
+    /// 
+    /// The piece slides along each hop of its path, captured pieces fade out as they're jumped and a crowned piece
+    /// flashes. The last frame is the board after the move, without the selected piece or highlights. Starting another
+    /// animation or calling [`Painter::cancel_animation`] stops this one without drawing any more frames
+    pub fn animate(&self, board: &Board, mv: &Move) -> Promise {
+        self.cancel_animation();
+        let generation = self.animation.get();
+
+        let mut painter = self.clone();
+        painter.clear_selected();
+        painter.clear_highlights();
+
+        let board = board.clone();
+        let mv = mv.clone();
+        let after = mv.apply(&board);
+
+        let slide = self.animation_duration;
+        let flash = match mv.promotes() {
+            true => slide * PROMOTION_FLASH_PROPORTION,
+            false => 0.0,
+        };
+
+        Promise::new(&mut |resolve: Function, _reject: Function| {
+            // the closure holds onto itself to request each following frame, until it's dropped at the end
+            let frame: Rc<RefCell<Option<Frame>>> = Rc::new(RefCell::new(None));
+            let next_frame = frame.clone();
+
+            let (painter, board, mv, after) = (painter.clone(), board.clone(), mv.clone(), after.clone());
+            let mut start = None;
+
+            *frame.borrow_mut() = Some(Closure::new(move |time: f64| {
+                let elapsed = time - *start.get_or_insert(time);
+                let cancelled = painter.animation.get() != generation;
+
+                if cancelled || elapsed >= slide + flash {
+                    if !cancelled {
+                        painter.draw(&after);
+                    }
+
+                    if let Err(err) = resolve.call0(&JsValue::NULL) {
+                        log!("Failed to resolve animation, {:?}", err);
+                    }
+
+                    next_frame.borrow_mut().take();
+                    return;
+                }
+
+                if elapsed < slide {
+                    painter.draw_slide(&board, &mv, elapsed / slide);
+                } else {
+                    painter.draw_flash(&after, mv.to(), (elapsed - slide) / flash);
+                }
+
+                if let Some(next) = next_frame.borrow().as_ref() {
+                    Painter::request_animation_frame(next);
+                }
+            }));
+
+            let first = frame.borrow();
+            if let Some(first) = first.as_ref() {
+                Painter::request_animation_frame(first);
+            }
+        })
+    }
+
+    /// Animate the last move of a game from the board it was played on, see [`Painter::animate`]
+    /// 
+    /// Resolves straight away when there's no move to animate
+    pub fn animate_last_move(&self, game: &Game) -> Promise {
+        match game.last_move() {
+            Some(mv) => self.animate(game.previous_board(game.ply() - 1), &mv),
+            None => Promise::resolve(&JsValue::NULL),
+        }
+    }
+
     /// Reset the canvas dimensions to the given width and height
     pub fn reset_dimensions(&self) {
         self.canvas.set_width(self.width);
@@ -238,12 +696,14 @@ impl Painter {
     pub fn validate_board_dim(&self, board: &Board) -> bool {
         let mut ans = true;
 
-        if self.height as usize % board.height != 0 {
+        let height_remainder = self.height as usize % board.height;
+        if height_remainder != 0 {
             log!("Canvas and board heights do not evenly divide, Canvas({}) / Board({}) = {} px/cell", self.height, board.height, self.height as f32 / board.height as f32);
             ans = false;
         }
 
-        if self.width as usize % board.width != 0 {
+        let width_remainder = self.width as usize % board.width;
+        if width_remainder != 0 {
             log!("Canvas and board widths do not evenly divide, Canvas({}) / Board({}) = {} px/cell", self.width, board.width, self.width as f32 / board.width as f32);
             ans = false;
         }
@@ -271,55 +731,19 @@ impl Painter {
         let piece_outline = cell_radius * self.piece_line_proportion;
         let square_outline = min_dimension * self.outline_width;
 
-        self.context.set_fill_style(&self.white_square);
-        self.context.fill_rect(0.0, 0.0, self.width as f64, self.height as f64);
+        self.draw_squares(board, cell_width, cell_height, square_outline);
 
-        self.context.set_fill_style(&self.black_square);
-        self.context.set_stroke_style(&self.square_outline);
-        self.context.set_line_width(square_outline);
+        // Draw last move under the pieces
+        for highlight in [Highlight::LastFrom, Highlight::LastTo] {
+            self.context.set_fill_style_str(self.highlight_colour(highlight));
 
-        // Draw black squares onto canvas
-        for i in 0..board.height {
-            for j in 0..board.width {
-
-                if i % 2 == 0 {
-                    if j % 2 == 1 {
-                        self.context.fill_rect(
-                            (j * cell_width) as f64, 
-                            (i * cell_height) as f64, 
-                            cell_width as f64, 
-                            cell_height as f64
-                        );
-
-                        if self.draw_outline {
-                            self.context.stroke_rect(
-                                (j * cell_width) as f64, 
-                                (i * cell_height) as f64, 
-                                cell_width as f64, 
-                                cell_height as f64
-                            );
-                        }
-                    }
-                }
-                else {
-                    if j % 2 == 0 {
-                        self.context.fill_rect(
-                            (j * cell_width) as f64, 
-                            (i * cell_height) as f64, 
-                            cell_width as f64, 
-                            cell_height as f64
-                        );
-
-                        if self.draw_outline {
-                            self.context.stroke_rect(
-                                (j * cell_width) as f64, 
-                                (i * cell_height) as f64, 
-                                cell_width as f64, 
-                                cell_height as f64
-                            );
-                        }
-                    }
-                }
+            for idx in self.highlighted(highlight) {
+                self.context.fill_rect(
+                    (idx.col * cell_width) as f64, 
+                    (idx.row * cell_height) as f64, 
+                    cell_width as f64, 
+                    cell_height as f64
+                );
             }
         }
 
@@ -330,44 +754,13 @@ impl Painter {
 
                     let brd_idx = board.board_index(idx);
 
-                    match piece.team {
-                        Black => {
-                            self.context.set_fill_style(&self.black_piece);
-                            self.context.set_stroke_style(&self.black_piece_line);
-                        },
-                        White => {
-                            self.context.set_fill_style(&self.white_piece);
-                            self.context.set_stroke_style(&self.white_piece_line);
-                        },
-                    }
-
-                    if piece.strength == King {
-                        self.context.set_stroke_style(&self.king_line);
-                    }
-
                     let center_x: f64 = (brd_idx.col as f64 * cell_width as f64) + (cell_width as f64) / 2.0;
                     let center_y: f64 = (brd_idx.row as f64 * cell_height as f64) + (cell_height as f64) / 2.0;
 
-                    self.context.begin_path();
-                    match self.context.arc(
-                        center_x, 
-                        center_y, 
-                        cell_radius, // radius 
-                        0.0, // start angle
-                        f64::consts::PI * 2.0) // end angle
-                    {
-                        Ok(res) => res,
-                        Err(err) => log!("Failed to draw piece, idx: {}, square: {:?}, {:?}", idx, square, err),
-                    };
-                    self.context.fill();
-
-                    if self.piece_lines {
-                        self.context.set_line_width(piece_outline);
-                        self.context.stroke()
-                    }
-
+                    self.draw_piece(piece, center_x, center_y, cell_radius, piece_outline);
                 },
-                None => panic!("No piece found when attempting to draw, idx: {}, square: {:?}", idx, square),
+                // inconsistent square, leave it empty rather than losing the session
+                None => log!("No piece found when attempting to draw, idx: {}, square: {:?}", idx, square),
             }
         }
 
@@ -396,5 +789,50 @@ impl Painter {
                 self.context.stroke()
             }
         }
+
+        self.context.set_line_width(piece_outline);
+
+        // Ring pieces that have to capture
+        self.context.set_stroke_style_str(&self.forced_capture);
+        for idx in self.highlighted(Highlight::ForcedCapture) {
+            let center_x: f64 = (idx.col as f64 * cell_width as f64) + (cell_width as f64) / 2.0;
+            let center_y: f64 = (idx.row as f64 * cell_height as f64) + (cell_height as f64) / 2.0;
+
+            self.context.begin_path();
+            match self.context.arc(center_x, center_y, cell_radius + piece_outline, 0.0, f64::consts::PI * 2.0) {
+                Ok(res) => res,
+                Err(err) => log!("Failed to paint forced capture, idx: {}, {:?}", idx, err),
+            };
+            self.context.stroke();
+        }
+
+        // Cross out pieces about to be captured
+        self.context.set_stroke_style_str(&self.captured);
+        for idx in self.highlighted(Highlight::Captured) {
+            let center_x: f64 = (idx.col as f64 * cell_width as f64) + (cell_width as f64) / 2.0;
+            let center_y: f64 = (idx.row as f64 * cell_height as f64) + (cell_height as f64) / 2.0;
+            let offset = cell_radius * f64::consts::FRAC_1_SQRT_2;
+
+            self.context.begin_path();
+            self.context.move_to(center_x - offset, center_y - offset);
+            self.context.line_to(center_x + offset, center_y + offset);
+            self.context.move_to(center_x + offset, center_y - offset);
+            self.context.line_to(center_x - offset, center_y + offset);
+            self.context.stroke();
+        }
+
+        // Dot squares the selected piece can move to
+        self.context.set_fill_style_str(&self.destination);
+        for idx in self.highlighted(Highlight::Destination) {
+            let center_x: f64 = (idx.col as f64 * cell_width as f64) + (cell_width as f64) / 2.0;
+            let center_y: f64 = (idx.row as f64 * cell_height as f64) + (cell_height as f64) / 2.0;
+
+            self.context.begin_path();
+            match self.context.arc(center_x, center_y, cell_radius * DESTINATION_PROPORTION, 0.0, f64::consts::PI * 2.0) {
+                Ok(res) => res,
+                Err(err) => log!("Failed to paint destination, idx: {}, {:?}", idx, err),
+            };
+            self.context.fill();
+        }
     }
 }
\ No newline at end of file
@@ -1,4 +1,4 @@
-import { Game, Board, BrdIdx, Painter, Team, init_wasm, Moveable, SquareState, Square } from "draught";
+import { Game, Board, BrdIdx, Painter, Team, init_wasm, Moveable, SquareState, Square, GameResult } from "draught";
 // import { memory } from "draught/draught_bg.wasm";
 
 ///////////////////
@@ -17,6 +17,11 @@ var PERFECT_CHANCE = 0.5;
 
 const STATUS_TIMEOUT = 3000;
 const WON_TIMEOUT = 3000;
+// milliseconds for the AI's pieces to slide across the board
+const ANIMATION_DURATION = 400;
+
+// localStorage key for the game in progress
+const SAVE_KEY = "draught.game";
 
 const GameState = {
     HUMAN_TURN: {
@@ -43,6 +48,43 @@ const winningText = document.getElementById("winning-p");
 const startBtn = document.getElementById("startBtn");
 startBtn.onclick = start_game;
 
+/**
+ * Step through the game's history, with the AI playing this goes back to or forward from the player's own turn
+ */
+function stepHistory(step) {
+    if (!step()) {
+        return;
+    }
+
+    // the AI's move may still be animating on the board being left
+    painter.cancel_animation();
+
+    if (aiCheckBox.checked && game.current_turn() === Team.White) {
+        step();
+    }
+
+    if (wonTimeout != null) {
+        clearInterval(wonTimeout);
+        wonTimeout = null;
+    }
+
+    clicks = [];
+    painter.clear_selected();
+    drawGame();
+    current_state = GameState.HUMAN_TURN.THINKING;
+
+    updateTeamText();
+    updateWinningText();
+    checkWon();
+    saveGame();
+}
+
+const undoBtn = document.getElementById("undoBtn");
+undoBtn.onclick = () => stepHistory(() => game.undo());
+
+const redoBtn = document.getElementById("redoBtn");
+redoBtn.onclick = () => stepHistory(() => game.redo());
+
 let wonTimeout = null;
 let statusTimeout = null;
 let setStatus = setStatusAlert;
@@ -54,8 +96,6 @@ let painter = null;
 
 let clicks = [];
 
-start_game();
-
 /////////////////
 //   CANVAS
 /////////////////
@@ -77,21 +117,94 @@ canvas.addEventListener("click", (event) => {
 ////////////////
 
 function start_game() {
+    // stop the last game's animation drawing over the new one
+    if (painter !== null) {
+        painter.cancel_animation();
+    }
+
     game = new Game(BOARD_WIDTH, BOARD_HEIGHT, PIECE_ROWS, Team.Black, SEARCH_DEPTH);
     painter = new Painter(CANVAS_WIDTH, CANVAS_HEIGHT, "game-canvas");
+    painter.set_animation_duration(ANIMATION_DURATION);
     // game.set_painter(painter);
     // game.draw();
-    painter.draw_current(game);
+    drawGame();
 
     clearInterval(wonTimeout);
     updateTeamText();
     updateWinningText();
     clicks = [];
     current_state = GameState.HUMAN_TURN.THINKING;
+    saveGame();
+}
+
+/**
+ * Draw the current board, highlighting the last move and where the selected piece can go
+ */
+function drawGame() {
+    painter.highlight_moves(game);
+    painter.draw_current(game);
+}
+
+/**
+ * Store the game in localStorage so that it can be carried on after a reload
+ */
+function saveGame() {
+    try {
+        localStorage.setItem(SAVE_KEY, game.to_json());
+    } catch (e) {
+        console.error(e);
+    }
+}
+
+/**
+ * Carry on with the game stored in localStorage, returns whether there was one to load
+ */
+function loadGame() {
+    let saved = localStorage.getItem(SAVE_KEY);
+    if (saved === null) {
+        return false;
+    }
+
+    try {
+        game = Game.from_json(saved);
+    } catch (e) {
+        console.error(e);
+        localStorage.removeItem(SAVE_KEY);
+        return false;
+    }
+
+    let rules = game.rules();
+    BOARD_WIDTH = rules.width;
+    BOARD_HEIGHT = rules.height;
+    PIECE_ROWS = rules.piece_rows;
+    SEARCH_DEPTH = game.search_depth();
+    PERFECT_CHANCE = game.perfect_chance;
+
+    widthBox.value = BOARD_WIDTH;
+    heightBox.value = BOARD_HEIGHT;
+    pieceRowsBox.max = (BOARD_HEIGHT / 2) - 1;
+    pieceRowsBox.value = PIECE_ROWS;
+    aiSearchDepthBox.value = SEARCH_DEPTH;
+    aiPerfectChance.value = Math.round(PERFECT_CHANCE * 100);
+
+    painter = new Painter(CANVAS_WIDTH, CANVAS_HEIGHT, "game-canvas");
+    painter.set_animation_duration(ANIMATION_DURATION);
+    drawGame();
+
+    updateTeamText();
+    updateWinningText();
+    clicks = [];
+    current_state = GameState.HUMAN_TURN.THINKING;
+    checkWon();
+
+    return true;
 }
 
 function process_canvas_click(cell_coord) {
 
+    // resolves once the AI's reply has been shown
+    let animation = null;
+
     switch(current_state) {
         // first click of a move
         case GameState.HUMAN_TURN.THINKING:
@@ -110,7 +223,7 @@ function process_canvas_click(cell_coord) {
             game.set_selected(cell_coord);
             painter.set_selected(cell_coord);
             // game.draw();
-            painter.draw_current(game);
+            drawGame();
 
             break;
             
@@ -136,20 +249,40 @@ function process_canvas_click(cell_coord) {
                     return;
                 }
 
-                let status = game.make_move(clicks[0], clicks[1]);
+                let status = Moveable.Allowed;
+
+                try {
+                    game.make_move(clicks[0], clicks[1]);
+                } catch (e) {
+                    // illegal moves carry the reason that they weren't made
+                    status = e.reason;
+                }
 
                 switch(status) {
                     case Moveable.Allowed:
 
-                        if (aiCheckBox.checked && game.has_won() === undefined) {
+                        // part way through a multi-jump, the same piece has to carry on
+                        if (game.jumping_piece() !== undefined) {
+                            break;
+                        }
+
+                        if (aiCheckBox.checked && game.result() === undefined) {
 
                             let start = performance.now();
 
-                            game.ai_move();
+                            try {
+                                game.ai_move();
+                            } catch (e) {
+                                setStatus(`Error: ${e.message}`);
+                                console.error(e);
+                                break;
+                            }
 
                             let end = performance.now();
 
-                            nodeCountText.innerText = `searched ${game.last_node_count.toLocaleString("en-GB")} possible moves in ${(end - start).toLocaleString()}ms`;
+                            nodeCountText.innerText = `searched ${game.last_node_count.toLocaleString("en-GB")} possible moves ${game.last_depth} moves ahead in ${(end - start).toLocaleString()}ms`;
+
+                            animation = painter.animate_last_move(game);
                         }
 
                         break;
@@ -177,16 +310,51 @@ function process_canvas_click(cell_coord) {
                     case Moveable.WrongTeamSrc:
                         setStatus("That's not your piece!");
                         break;
+                    case Moveable.CaptureRequired:
+                        setStatus("You have to take a piece!");
+                        break;
+                    case Moveable.JumpInProgress:
+                        setStatus("You have to keep jumping with the same piece!");
+                        break;
+                    case Moveable.MaximumCaptureRequired:
+                        setStatus("You have to take as many pieces as you can!");
+                        break;
+                    case Moveable.GameOver:
+                        setStatus("The game's over!");
+                        break;
                 }
                 
             }
 
+            let jumping = game.jumping_piece();
+
+            // keep the jumping piece selected for the next hop
+            if (jumping !== undefined) {
+                clicks = [jumping];
+                game.set_selected(jumping);
+                painter.set_selected(jumping);
+                drawGame();
+                current_state = GameState.HUMAN_TURN.FROM_SELECTED;
+
+                break;
+            }
+
             game.clear_selected();
             painter.clear_selected();
-            // game.draw();
-            painter.draw_current(game);
             clicks = [];
-            current_state = GameState.HUMAN_TURN.THINKING;
+
+            if (animation !== null) {
+                // ignore clicks until the AI's move has finished animating
+                current_state = GameState.AI_TURN;
+                animation.then(() => {
+                    current_state = GameState.HUMAN_TURN.THINKING;
+                    drawGame();
+                });
+            } else {
+                // game.draw();
+                drawGame();
+                current_state = GameState.HUMAN_TURN.THINKING;
+            }
             
             break;
         case GameState.AI_TURN:
@@ -197,6 +365,7 @@ function process_canvas_click(cell_coord) {
     updateTeamText();
     updateWinningText();
     checkWon();
+    saveGame();
 }
 
 function getMousePos(canvas, evt) {
@@ -266,21 +435,27 @@ function updateWinningText(){
 
 function checkWon() {
 
-    switch(game.has_won()) {
+    switch(game.result()) {
         case undefined:
             break;
-        case Team.White:
+        case GameResult.WhiteWin:
             setStatus("You Lost!");
             wonTimeout = setInterval(() => {
                 start_game();
             }, WON_TIMEOUT);
             break;
-        case Team.Black:
+        case GameResult.BlackWin:
             setStatus("You Won!", "success");
             wonTimeout = setInterval(() => {
                 start_game();
             }, WON_TIMEOUT);
             break;
+        case GameResult.Draw:
+            setStatus("Draw!", "warning");
+            wonTimeout = setInterval(() => {
+                start_game();
+            }, WON_TIMEOUT);
+            break;
     }
 }
 
@@ -333,9 +508,10 @@ const onAISearchDepth = () => {
 
     SEARCH_DEPTH = parseInt(aiSearchDepthBox.value);
     game.set_search_depth(SEARCH_DEPTH);
+    saveGame();
 
-    if(SEARCH_DEPTH > 4) {
-        setStatus("This increases thinking time exponentially, be careful (probably don't go past 6)", "warning");
+    if(SEARCH_DEPTH > 8) {
+        setStatus("This increases thinking time exponentially, be careful (probably don't go past 10)", "warning");
     }
 }
 aiSearchDepthBox.onchange = onAISearchDepth;
@@ -359,6 +535,12 @@ const onPerfectChance = () => {
 
     PERFECT_CHANCE = parseInt(aiPerfectChance.value) / 100;
     game.set_perfect_chance(PERFECT_CHANCE);
+    saveGame();
 }
 aiPerfectChance.onchange = onPerfectChance;
-aiPerfectChance.value = 50;
\ No newline at end of file
+aiPerfectChance.value = 50;
+
+// carry on from before the page was reloaded
+if (!loadGame()) {
+    start_game();
+}
@@ -0,0 +1,622 @@
+//! Bitboard positions for fast move generation
+//!
+//! Only playable squares are stored, one bit each, so that moving a set of pieces one square along a diagonal is a single
+//! shift. Each row has `width / 2` playable squares and a spare "ghost" bit is left after every pair of rows, with that gap
+//! the diagonal neighbours of every square are always `width / 2` and `width / 2 + 1` bits away and moves off the side
+//! of the board land on a ghost bit which is masked out. 8x8 boards take 35 bits and 10x10 boards take 54.
+//!
+//! Convert to and from a [`Board`] with [`BitBoard::from_board`] and [`BitBoard::to_board`]
+
+use std::fmt::{Display};
+
+use super::{Board, BrdIdx, Square};
+use super::enums::{Team, Strength};
+use super::iter::PieceIterator;
+use super::rules::{Rules, CaptureRule, Promotion};
+use crate::comp::Move;
+
+/// Problems making a [`BitBoard`] from a [`Board`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BitBoardError {
+    /// Board has an odd number of columns so the diagonals don't line up between rows
+    OddWidth(usize),
+    /// Board's playable squares don't fit in 64 bits, given as width and height
+    TooLarge(usize, usize),
+    /// Board is part-way through a multi-jump, only positions at the start of a turn can be stored
+    JumpInProgress,
+}
+
+impl Display for BitBoardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            BitBoardError::OddWidth(width) => write!(f, "Board width {} is odd", width),
+            BitBoardError::TooLarge(width, height) => write!(f, "{}x{} board is too large for a bitboard", width, height),
+            BitBoardError::JumpInProgress => write!(f, "Board is part-way through a multi-jump"),
+        }
+    }
+}
+
+impl std::error::Error for BitBoardError {}
+
+/// One diagonal direction as a shift, down the board is towards higher rows and higher bits
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Step {
+    shift: u32,
+    down: bool,
+}
+
+/// Single capture from a square, given as single bit masks
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Hop {
+    jumpee: u64,
+    land: u64,
+}
+
+/// Squares that don't change while a piece makes a capture sequence
+struct CaptureSearch {
+    from: u32,
+    man: bool,
+    /// Empty squares including the capturing piece's starting square
+    empty: u64,
+    /// Opposing pieces, captured pieces stay on the board until the end of the turn
+    opponent: u64,
+}
+
+/// Move on a [`BitBoard`] given by bit indices, see [`BitBoard::square`] for the board location of a bit
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BitMove {
+    from: u32,
+    to: u32,
+    /// Pieces taken by the move
+    captured: u64,
+    /// Landing bit of each hop of a capture, empty for a simple move
+    hops: Vec<u32>,
+    /// Whether a man is crowned by the move
+    promotes: bool,
+}
+
+impl BitMove {
+    /// Source bit of the move
+    pub fn from(&self) -> u32 {
+        self.from
+    }
+
+    /// Destination bit of the move, the last landing bit for a capture
+    pub fn to(&self) -> u32 {
+        self.to
+    }
+
+    /// Mask of the pieces taken by the move
+    pub fn captured(&self) -> u64 {
+        self.captured
+    }
+
+    /// Landing bit of each hop of a capture, empty for a simple move
+    pub fn hops(&self) -> &[u32] {
+        &self.hops
+    }
+
+    /// Whether the move takes any pieces
+    pub fn is_capture(&self) -> bool {
+        self.captured != 0
+    }
+
+    /// Whether a man is crowned by the move
+    pub fn promotes(&self) -> bool {
+        self.promotes
+    }
+}
+
+/// Position stored as a mask of playable squares for each team and for kings
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BitBoard {
+    white: u64,
+    black: u64,
+    kings: u64,
+    pub current_turn: Team,
+    rules: Rules,
+    /// Playable squares per row, the shorter diagonal shift
+    half: u32,
+    /// Every playable square, ghost bits and bits past the end of the board are clear
+    valid: u64,
+}
+
+impl BitBoard {
+    /// Create an empty bitboard for the given rules
+    pub fn new(rules: Rules, current_turn: Team) -> Result<BitBoard, BitBoardError> {
+        let (width, height) = (rules.width, rules.height);
+
+        if width & 1 != 0 || width == 0 {
+            return Err(BitBoardError::OddWidth(width));
+        }
+
+        // rows alternate between half and half + 1 bits including the ghost bits, less the ghost after the last pair
+        if (height * (width + 1)).saturating_sub(1) / 2 > 64 {
+            return Err(BitBoardError::TooLarge(width, height));
+        }
+
+        let half = (width / 2) as u32;
+        let mut valid = 0;
+        for row in 0..height {
+            for col in (1 - row % 2..width).step_by(2) {
+                valid |= 1 << BitBoard::bit_of(half, row, col);
+            }
+        }
+
+        Ok(BitBoard {
+            white: 0,
+            black: 0,
+            kings: 0,
+            current_turn,
+            rules,
+            half,
+            valid,
+        })
+    }
+
+    /// Get a bitboard for the same rules with the given pieces, squares that aren't playable are dropped
+    pub fn with_pieces(&self, white: u64, black: u64, kings: u64, current_turn: Team) -> BitBoard {
+        BitBoard {
+            white: white & self.valid,
+            black: black & self.valid,
+            kings: kings & (white | black) & self.valid,
+            current_turn,
+            ..*self
+        }
+    }
+
+    /// Create a bitboard with the same position as a board
+    pub fn from_board(board: &Board) -> Result<BitBoard, BitBoardError> {
+        if board.jumping_piece().is_some() {
+            return Err(BitBoardError::JumpInProgress);
+        }
+
+        let mut bitboard = BitBoard::new(board.rules(), board.current_turn)?;
+
+        for (idx, square) in PieceIterator::new(board) {
+            if let Some(piece) = square.occupant {
+                let brd_idx = board.board_index(idx);
+                let bit = 1 << BitBoard::bit_of(bitboard.half, brd_idx.row, brd_idx.col);
+
+                match piece.team {
+                    Team::White => bitboard.white |= bit,
+                    Team::Black => bitboard.black |= bit,
+                }
+
+                if piece.strength == Strength::King {
+                    bitboard.kings |= bit;
+                }
+            }
+        }
+
+        Ok(bitboard)
+    }
+
+    /// Get a board with the same position
+    pub fn to_board(&self) -> Board {
+        let mut board = Board::with_rules(self.rules, self.current_turn);
+
+        for (bit, team) in bits(self.white).map(|b| (b, Team::White)).chain(bits(self.black).map(|b| (b, Team::Black))) {
+            let strength = match self.kings & (1 << bit) {
+                0 => Strength::Man,
+                _ => Strength::King,
+            };
+
+            let cell_idx = board.cell_idx(self.square(bit));
+            board.cells[cell_idx] = Square::pc(team, strength);
+        }
+
+        board
+    }
+
+    /// Bit index for a square, callers make sure the square is playable
+    fn bit_of(half: u32, row: usize, col: usize) -> u32 {
+        (row / 2) as u32 * (2 * half + 1) + (row % 2) as u32 * half + (col / 2) as u32
+    }
+
+    /// Get the bit index for a board location, [`None`] if the square is unplayable or off the board
+    pub fn bit(&self, idx: BrdIdx) -> Option<u32> {
+        if idx.row >= self.rules.height || idx.col >= self.rules.width || (idx.row + idx.col) & 1 == 0 {
+            return None;
+        }
+
+        Some(BitBoard::bit_of(self.half, idx.row, idx.col))
+    }
+
+    /// Get the board location of a playable bit index
+    pub fn square(&self, bit: u32) -> BrdIdx {
+        let pair = bit / (2 * self.half + 1);
+        let offset = bit % (2 * self.half + 1);
+
+        if offset < self.half {
+            BrdIdx::from(2 * pair as usize, 2 * offset as usize + 1)
+        } else {
+            BrdIdx::from(2 * pair as usize + 1, 2 * (offset - self.half) as usize)
+        }
+    }
+
+    /// Rules that moves are generated with
+    pub fn rules(&self) -> Rules {
+        self.rules
+    }
+
+    /// Mask of every playable square
+    pub fn playable(&self) -> u64 {
+        self.valid
+    }
+
+    /// Mask of a team's pieces
+    pub fn pieces(&self, team: Team) -> u64 {
+        match team {
+            Team::White => self.white,
+            Team::Black => self.black,
+        }
+    }
+
+    /// Mask of every king of both teams
+    pub fn kings(&self) -> u64 {
+        self.kings
+    }
+
+    /// Mask of every empty playable square
+    pub fn empty(&self) -> u64 {
+        self.valid & !(self.white | self.black)
+    }
+
+    /// Number of pieces for a team
+    pub fn num_player(&self, team: Team) -> usize {
+        self.pieces(team).count_ones() as usize
+    }
+
+    /// Piece count with kings worth 2, positive is good for Black and negative is good for White as in [`Board::score`]
+    pub fn score(&self) -> isize {
+        let value = |team: Team| (self.pieces(team).count_ones() + (self.pieces(team) & self.kings).count_ones()) as isize;
+
+        value(Team::Black) - value(Team::White)
+    }
+
+    /// Mask of the row where a team's men are crowned
+    pub fn king_row(&self, team: Team) -> u64 {
+        let row = match team {
+            Team::White => self.rules.height - 1,
+            Team::Black => 0,
+        };
+
+        let first = BitBoard::bit_of(self.half, row, 1 - row % 2);
+        ((u64::MAX >> (64 - self.half)) << first) & self.valid
+    }
+
+    /// Both diagonal directions towards the given side of the board
+    fn steps(&self, down: bool) -> [Step; 2] {
+        [
+            Step { shift: self.half, down },
+            Step { shift: self.half + 1, down },
+        ]
+    }
+
+    /// Every diagonal direction
+    fn all_steps(&self) -> [Step; 4] {
+        let [a, b] = self.steps(true);
+        let [c, d] = self.steps(false);
+        [a, b, c, d]
+    }
+
+    /// Whether the team moves towards higher rows
+    fn forward_down(team: Team) -> bool {
+        team == Team::White
+    }
+
+    /// Move every bit of the mask one square along a diagonal, dropping those that go off the board
+    fn shift(&self, bits: u64, step: Step) -> u64 {
+        let shifted = if step.down { bits << step.shift } else { bits >> step.shift };
+        shifted & self.valid
+    }
+
+    /// Square a piece came from to land on the given bit index
+    fn back(to: u32, step: Step) -> u32 {
+        if step.down { to - step.shift } else { to + step.shift }
+    }
+
+    /// Get every legal move for the player to move, captures are forced and limited by the [`CaptureRule`]
+    pub fn moves(&self) -> Vec<BitMove> {
+        let captures = self.captures();
+
+        if !captures.is_empty() {
+            return match self.rules.capture_rule {
+                CaptureRule::Free => captures,
+                CaptureRule::Majority => {
+                    let longest = captures.iter().map(|m| m.hops.len()).max().unwrap_or(0);
+                    captures.into_iter().filter(|m| m.hops.len() == longest).collect()
+                },
+            };
+        }
+
+        self.simple_moves()
+    }
+
+    /// Get every non-capturing move for the player to move, regardless of whether a capture is available
+    pub fn simple_moves(&self) -> Vec<BitMove> {
+        let team = self.current_turn;
+        let own = self.pieces(team);
+        let empty = self.empty();
+        let king_row = self.king_row(team);
+        let forward = BitBoard::forward_down(team);
+
+        // flying kings are done one at a time below
+        let stepping_kings = if self.rules.flying_kings { 0 } else { own & self.kings };
+        let men = own & !self.kings;
+
+        let mut moves = Vec::with_capacity(own.count_ones() as usize * 2);
+
+        for step in self.all_steps() {
+            let movers = match step.down == forward {
+                true => men | stepping_kings,
+                false => stepping_kings,
+            };
+
+            for to in bits(self.shift(movers, step) & empty) {
+                let from = BitBoard::back(to, step);
+
+                moves.push(BitMove {
+                    from,
+                    to,
+                    captured: 0,
+                    hops: Vec::new(),
+                    promotes: men & (1 << from) != 0 && king_row & (1 << to) != 0,
+                });
+            }
+        }
+
+        if self.rules.flying_kings {
+            for from in bits(own & self.kings) {
+                for step in self.all_steps() {
+                    let mut square = self.shift(1 << from, step);
+
+                    while square & empty != 0 {
+                        moves.push(BitMove {
+                            from,
+                            to: square.trailing_zeros(),
+                            captured: 0,
+                            hops: Vec::new(),
+                            promotes: false,
+                        });
+                        square = self.shift(square, step);
+                    }
+                }
+            }
+        }
+
+        moves
+    }
+
+    /// Mask of the player to move's pieces that may have a capture
+    ///
+    /// Exact for men and short kings, flying kings are always included
+    fn capture_candidates(&self) -> u64 {
+        let team = self.current_turn;
+        let own = self.pieces(team);
+        let opponent = self.pieces(team.opponent());
+        let empty = self.empty();
+        let forward = BitBoard::forward_down(team);
+
+        let flying = if self.rules.flying_kings { own & self.kings } else { 0 };
+        let men = own & !self.kings;
+        let stepping_kings = own & self.kings & !flying;
+
+        let mut candidates = flying;
+
+        for step in self.all_steps() {
+            let movers = if step.down == forward || self.rules.men_capture_backwards {
+                men | stepping_kings
+            } else {
+                stepping_kings
+            };
+
+            let reverse = Step { down: !step.down, ..step };
+            let landings = self.shift(self.shift(movers, step) & opponent, step) & empty;
+            candidates |= self.shift(self.shift(landings, reverse), reverse);
+        }
+
+        candidates & own
+    }
+
+    /// Get every capture sequence for the player to move, before the [`CaptureRule`] is applied
+    pub fn captures(&self) -> Vec<BitMove> {
+        let team = self.current_turn;
+        let own = self.pieces(team);
+        let opponent = self.pieces(team.opponent());
+        let mut moves = Vec::new();
+        let mut path = Vec::new();
+
+        for from in bits(self.capture_candidates()) {
+            let king = self.kings & (1 << from) != 0;
+            let search = CaptureSearch {
+                from,
+                man: !king,
+                empty: self.valid & !((own & !(1 << from)) | opponent),
+                opponent,
+            };
+
+            let hops = self.hops(&search, 1 << from, king, 0);
+            self.extend_captures(&search, hops, king, 0, &mut path, &mut moves);
+        }
+
+        moves
+    }
+
+    /// Every single capture that a piece on the given square could make next
+    fn hops(&self, search: &CaptureSearch, square: u64, king: bool, captured: u64) -> Vec<Hop> {
+        let jumpable = search.opponent & !captured;
+        let forward = BitBoard::forward_down(self.current_turn);
+        let mut hops = Vec::new();
+
+        for step in self.all_steps() {
+            if !king && !self.rules.men_capture_backwards && step.down != forward {
+                continue;
+            }
+
+            if king && self.rules.flying_kings {
+                // slide up to the first piece then land on any empty square behind it
+                let mut jumpee = self.shift(square, step);
+                while jumpee & search.empty != 0 {
+                    jumpee = self.shift(jumpee, step);
+                }
+
+                if jumpee & jumpable == 0 {
+                    continue;
+                }
+
+                let mut land = self.shift(jumpee, step);
+                while land & search.empty != 0 {
+                    hops.push(Hop { jumpee, land });
+                    land = self.shift(land, step);
+                }
+            } else {
+                let jumpee = self.shift(square, step) & jumpable;
+                let land = self.shift(jumpee, step) & search.empty;
+
+                if land != 0 {
+                    hops.push(Hop { jumpee, land });
+                }
+            }
+        }
+
+        hops
+    }
+
+    /// Follow each hop to the end of its capture sequences, see [`Board::apply_jump`] for how promotion affects a capture
+    fn extend_captures(&self, search: &CaptureSearch, hops: Vec<Hop>, king: bool, captured: u64, path: &mut Vec<u32>, moves: &mut Vec<BitMove>) {
+        let king_row = self.king_row(self.current_turn);
+
+        let next: Vec<(Hop, bool, Vec<Hop>)> = hops
+            .into_iter()
+            .map(|hop| {
+                let crowned = !king && hop.land & king_row != 0;
+                let (king, ends) = match self.rules.promotion {
+                    Promotion::EndsTurn => (king || crowned, crowned),
+                    Promotion::ContinuesAsKing => (king || crowned, false),
+                    Promotion::AtEndOfMove => (king, false),
+                };
+
+                let next = match ends {
+                    true => Vec::new(),
+                    false => self.hops(search, hop.land, king, captured | hop.jumpee),
+                };
+
+                (hop, king, next)
+            })
+            .collect();
+
+        // flying kings have to land where they can carry on capturing over the same piece if they can
+        let continuing = next
+            .iter()
+            .filter(|(_, _, next)| !next.is_empty())
+            .fold(0, |mask, (hop, _, _)| mask | hop.jumpee);
+
+        for (hop, king, next) in next {
+            let to = hop.land.trailing_zeros();
+            path.push(to);
+
+            if next.is_empty() {
+                if continuing & hop.jumpee == 0 {
+                    moves.push(BitMove {
+                        from: search.from,
+                        to,
+                        captured: captured | hop.jumpee,
+                        hops: path.clone(),
+                        promotes: search.man && (king || hop.land & king_row != 0),
+                    });
+                }
+            } else {
+                self.extend_captures(search, next, king, captured | hop.jumpee, path, moves);
+            }
+
+            path.pop();
+        }
+    }
+
+    /// Get a new bitboard with the move applied and the turn passed to the other player
+    pub fn apply(&self, mv: &BitMove) -> BitBoard {
+        let mut new = *self;
+        let (from, to) = (1u64 << mv.from, 1u64 << mv.to);
+
+        let (own, opponent) = match self.current_turn {
+            Team::White => (&mut new.white, &mut new.black),
+            Team::Black => (&mut new.black, &mut new.white),
+        };
+
+        *own = (*own & !from) | to;
+        *opponent &= !mv.captured;
+
+        let king = self.kings & from != 0 || mv.promotes;
+        new.kings &= !(from | mv.captured);
+        if king {
+            new.kings |= to;
+        }
+
+        new.current_turn = self.current_turn.opponent();
+        new
+    }
+
+    /// Count the move sequences of the given number of turns, see [`crate::comp::perft::perft`]
+    pub fn perft(&self, depth: usize) -> usize {
+        if depth == 0 {
+            return 1;
+        }
+
+        let moves = self.moves();
+
+        if depth == 1 {
+            return moves.len();
+        }
+
+        moves
+            .iter()
+            .map(|mv| self.apply(mv).perft(depth - 1))
+            .sum()
+    }
+
+    /// Get the equivalent [`Move`] for a [`Board`] in the same position, as given by [`Board::legal_moves`]
+    pub fn to_move(&self, mv: &BitMove) -> Move {
+        let from = self.square(mv.from);
+
+        if mv.hops.is_empty() {
+            return Move::step(from, self.square(mv.to), mv.promotes);
+        }
+
+        let hops: Vec<BrdIdx> = mv.hops.iter().map(|bit| self.square(*bit)).collect();
+
+        // each hop passes over one of the captured pieces
+        let mut captured = Vec::with_capacity(hops.len());
+        let mut start = from;
+        for hop in &hops {
+            let (row_step, col_step) = ((hop.row as isize - start.row as isize).signum(), (hop.col as isize - start.col as isize).signum());
+            let mut square = start;
+
+            while square != *hop {
+                square = BrdIdx::from((square.row as isize + row_step) as usize, (square.col as isize + col_step) as usize);
+
+                if matches!(self.bit(square), Some(bit) if mv.captured & (1 << bit) != 0) {
+                    captured.push(square);
+                }
+            }
+
+            start = *hop;
+        }
+
+        Move::capture(from, hops, captured, mv.promotes)
+    }
+}
+
+/// Iterate over the index of each set bit, lowest first
+pub fn bits(mut mask: u64) -> impl Iterator<Item = u32> {
+    std::iter::from_fn(move || {
+        if mask == 0 {
+            return None;
+        }
+
+        let bit = mask.trailing_zeros();
+        mask &= mask - 1;
+        Some(bit)
+    })
+}
@@ -2,6 +2,8 @@
 extern crate wasm_bindgen;
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
 
 use std::fmt::{Display};
 
@@ -9,6 +11,7 @@ use std::fmt::{Display};
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
 #[repr(u8)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum MoveType {
     Move = 0,
     Jump = 1,
@@ -18,6 +21,7 @@ pub enum MoveType {
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
 #[repr(u8)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Team {
     Black = 0,
     White = 1,
@@ -36,8 +40,8 @@ impl Team {
 impl Display for Team {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
-            Team::White => write!(f, "{}", 'W'),
-            Team::Black => write!(f, "{}", 'B'),
+            Team::White => write!(f, "W"),
+            Team::Black => write!(f, "B"),
         }
     }
 }
@@ -46,6 +50,7 @@ impl Display for Team {
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
 #[repr(u8)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Strength {
     Man = 0,
     King = 1
@@ -55,6 +60,7 @@ pub enum Strength {
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
 #[repr(u8)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum SquareState {
     Empty = 0,
     Occupied = 1,
@@ -64,9 +70,9 @@ pub enum SquareState {
 impl Display for SquareState {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
-            SquareState::Empty => write!(f, "{}", 'E'),
-            SquareState::Occupied => write!(f, "{}", 'O'),
-            SquareState::Unplayable => write!(f, "{}", 'U'),
+            SquareState::Empty => write!(f, "E"),
+            SquareState::Occupied => write!(f, "O"),
+            SquareState::Unplayable => write!(f, "U"),
         }
     }
 }
@@ -85,4 +91,68 @@ pub enum Moveable {
     IllegalTrajectory = 6,
     NoJumpablePiece = 7,
     JumpingSameTeam = 8,
-}
\ No newline at end of file
+    CaptureRequired = 9,
+    JumpInProgress = 10,
+    MaximumCaptureRequired = 11,
+    GameOver = 12,
+}
+
+impl Display for Moveable {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Moveable::Allowed => write!(f, "Move is allowed"),
+            Moveable::UnoccupiedSrc => write!(f, "There is no piece on the starting square"),
+            Moveable::OccupiedDest => write!(f, "The destination square is already occupied"),
+            Moveable::OutOfBounds => write!(f, "Square is off the board"),
+            Moveable::Unplayable => write!(f, "Pieces can't be played on that square"),
+            Moveable::WrongTeamSrc => write!(f, "That piece belongs to the other player"),
+            Moveable::IllegalTrajectory => write!(f, "That piece can't move there"),
+            Moveable::NoJumpablePiece => write!(f, "There is no opposing piece to jump"),
+            Moveable::JumpingSameTeam => write!(f, "Pieces can't jump their own team"),
+            Moveable::CaptureRequired => write!(f, "A capture is available and has to be taken"),
+            Moveable::JumpInProgress => write!(f, "The jumping piece has to finish its capture"),
+            Moveable::MaximumCaptureRequired => write!(f, "The capture taking the most pieces has to be taken"),
+            Moveable::GameOver => write!(f, "The game is already over"),
+        }
+    }
+}
+
+/// Final outcome of a game
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum GameResult {
+    BlackWin = 0,
+    WhiteWin = 1,
+    Draw = 2,
+}
+
+impl GameResult {
+    /// Get the result of a game won by the given team
+    pub fn win(team: Team) -> GameResult {
+        match team {
+            Team::Black => GameResult::BlackWin,
+            Team::White => GameResult::WhiteWin,
+        }
+    }
+}
+
+/// Whether a game is still being played and the reason that it finished if not
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum GameStatus {
+    InProgress = 0,
+    /// Player to move has no pieces left and has lost
+    NoPieces = 1,
+    /// Player to move has pieces but no legal moves and has lost
+    NoMoves = 2,
+    /// Same position has come up three times with the same player to move, drawn
+    Repetition = 3,
+    /// Too many moves without a capture or a man moving, drawn
+    MoveLimit = 4,
+    /// Players agreed to a draw
+    AgreedDraw = 5,
+}
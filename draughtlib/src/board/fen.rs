@@ -0,0 +1,131 @@
+//! Reading and writing [`Board`] positions as FEN strings
+//!
+//! Positions are given as the player to move then a list of squares for each team in numeric notation (see
+//! [`Board::square_number`]), kings are prefixed with `K` and runs of men can be written as a range.
+//! `W:W21,22,K23:B1-3` has White to move with men on 21 and 22, a king on 23 and Black men on 1, 2 and 3
+
+use std::fmt::{Display};
+
+use super::{Board, Square};
+use super::enums::{Team, Strength};
+use super::rules::Rules;
+
+/// Problems reading a FEN string
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FenError {
+    /// Player to move isn't `W` or `B`
+    InvalidTurn(String),
+    /// Section of squares doesn't start with `W` or `B`
+    InvalidTeam(String),
+    /// Same team is given more than one section of squares
+    DuplicateTeam(Team),
+    /// Square isn't a number, a range or a king
+    InvalidSquare(String),
+    /// Square number isn't on the board
+    SquareOutOfRange(usize),
+    /// Square is given more than once
+    DuplicateSquare(usize),
+}
+
+impl Display for FenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            FenError::InvalidTurn(turn) => write!(f, "Invalid player to move: {}", turn),
+            FenError::InvalidTeam(section) => write!(f, "Invalid team section: {}", section),
+            FenError::DuplicateTeam(team) => write!(f, "Squares given twice for {:?}", team),
+            FenError::InvalidSquare(square) => write!(f, "Invalid square: {}", square),
+            FenError::SquareOutOfRange(square) => write!(f, "Square {} is not on the board", square),
+            FenError::DuplicateSquare(square) => write!(f, "Square {} given more than once", square),
+        }
+    }
+}
+
+impl std::error::Error for FenError {}
+
+impl Board {
+    /// Create a board for the given rules with the position described by a FEN string
+    pub fn from_fen(fen: &str, rules: Rules) -> Result<Board, FenError> {
+        let fen = fen.trim().trim_matches('"').trim_end_matches('.');
+        let mut sections = fen.split(':');
+
+        let turn = sections.next().unwrap_or_default().trim();
+        let current_turn = parse_team(turn).ok_or_else(|| FenError::InvalidTurn(turn.to_string()))?;
+
+        let mut board = Board::with_rules(rules, current_turn);
+        let mut seen_teams = Vec::with_capacity(2);
+
+        for section in sections {
+            let section = section.trim();
+            let team = section.get(..1)
+                .and_then(parse_team)
+                .ok_or_else(|| FenError::InvalidTeam(section.to_string()))?;
+
+            if seen_teams.contains(&team) {
+                return Err(FenError::DuplicateTeam(team));
+            }
+            seen_teams.push(team);
+
+            for square in section[1..].split(',').map(str::trim).filter(|s| !s.is_empty()) {
+                let (strength, numbers) = match square.strip_prefix('K') {
+                    Some(numbers) => (Strength::King, numbers),
+                    None => (Strength::Man, square),
+                };
+
+                let invalid = || FenError::InvalidSquare(square.to_string());
+                let (first, last) = match numbers.split_once('-') {
+                    Some((first, last)) => (first.parse::<usize>().map_err(|_| invalid())?, last.parse::<usize>().map_err(|_| invalid())?),
+                    None => {
+                        let number = numbers.parse::<usize>().map_err(|_| invalid())?;
+                        (number, number)
+                    },
+                };
+
+                if first > last {
+                    return Err(invalid());
+                }
+
+                for number in first..=last {
+                    let idx = board.square_idx(number).ok_or(FenError::SquareOutOfRange(number))?;
+                    let cell_idx = board.cell_idx(idx);
+
+                    if board.cell(cell_idx).occupant.is_some() {
+                        return Err(FenError::DuplicateSquare(number));
+                    }
+
+                    board.cells[cell_idx] = Square::pc(team, strength);
+                }
+            }
+        }
+
+        Ok(board)
+    }
+
+    /// Get a FEN string describing the board's position
+    ///
+    /// Only the position and player to move are kept, a multi-jump part-way through is lost
+    pub fn to_fen(&self) -> String {
+        let squares = |team: Team| -> Vec<String> {
+            (1..)
+                .map_while(|number| self.square_idx(number).map(|idx| (number, idx)))
+                .filter_map(|(number, idx)| match self.grid_cell(idx).occupant {
+                    Some(piece) if piece.team == team => match piece.strength {
+                        Strength::Man => Some(number.to_string()),
+                        Strength::King => Some(format!("K{}", number)),
+                    },
+                    _ => None,
+                })
+                .collect()
+        };
+
+        format!("{}:W{}:B{}", self.current_turn, squares(Team::White).join(","), squares(Team::Black).join(","))
+    }
+}
+
+/// Team for a colour code
+fn parse_team(code: &str) -> Option<Team> {
+    match code {
+        "W" => Some(Team::White),
+        "B" => Some(Team::Black),
+        _ => None,
+    }
+}
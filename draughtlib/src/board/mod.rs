@@ -1,6 +1,8 @@
 //! Board module for components related to the checkers board and game structure
 
-#[cfg(test)] pub mod tests;
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::bool_assert_comparison)]
+pub mod tests;
 
 pub mod enums;
 use enums::*;
@@ -11,15 +13,27 @@ use enums::SquareState::*;
 pub mod iter;
 use iter::*;
 
+pub mod rules;
+use rules::*;
+
+pub mod fen;
+pub mod zobrist;
+pub mod bitboard;
+pub mod moves;
+
 use std::fmt::{Display, Write};
 use std::option::Option;
 
+use crate::error::DraughtError;
+
 // use draught_web::log;
 
 #[cfg(target_arch = "wasm32")]
 extern crate wasm_bindgen;
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
 
 /// Standard width of a checkers board is 8 squares
 pub const STD_WIDTH: usize = 8;
@@ -29,6 +43,7 @@ pub const STD_HEIGHT: usize = 8;
 /// Game piece given by its team and strength (normal or kinged)
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Piece {
     pub team: Team,
     pub strength: Strength
@@ -76,6 +91,7 @@ impl<T: Clone + Copy> Direction<T> {
 /// Board squares given by a state and a possible occupying game piece
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Square {
     /// Game piece if square is occupied
     pub occupant: Option<Piece>,
@@ -122,6 +138,7 @@ impl Square {
 /// Rank 2 tensor index to identify a board square by row and column
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct BrdIdx {
     pub row: usize,
     pub col: usize
@@ -136,6 +153,7 @@ impl BrdIdx {
         }
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn eq (&self, other: &BrdIdx) -> bool {
         *self == *other
     }
@@ -154,13 +172,20 @@ impl Display for BrdIdx {
 /// Single state of a checkers board
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Board {
     /// 1D backing array of board squares for the 2D game board
     cells: Vec<Square>,
     pub width: usize,
     pub height: usize,
 
-    pub current_turn: Team
+    pub current_turn: Team,
+    /// Piece part-way through a multi-jump, only this piece can move until the turn is over
+    jumping: Option<BrdIdx>,
+    /// 1D indices of pieces taken so far in a multi-jump, these are removed from the board when the turn is over
+    captured: Vec<usize>,
+    /// Rules of the variant being played
+    rules: Rules,
 }
 
 ////////////////////
@@ -274,10 +299,7 @@ impl Board {
     }
 
     pub fn player_adjacent_indices(&self, idx: BrdIdx, player: Team) -> Option<Vec<usize>> {
-        match self.adjacent_indices(idx) {
-            Some(x) => Some(self.filter_indices(idx, player, x)),
-            None => None
-        }
+        self.adjacent_indices(idx).map(|x| self.filter_indices(idx, player, x))
     }
 
     /// Get the 1D array indices for the diagonally jumpable squares of a given board square
@@ -371,10 +393,109 @@ impl Board {
     }
 
     pub fn player_jumpable_indices(&self, idx: BrdIdx, player: Team) -> Option<Vec<usize>> {
-        match self.jumpable_indices(idx) {
-            Some(x) => Some(self.filter_indices(idx, player, x)),
-            None => None
+        self.jumpable_indices(idx).map(|x| self.filter_indices(idx, player, x))
+    }
+
+    /// Finish the current player's turn, clear any pieces taken in a multi-jump and hand over to the opponent
+    fn end_turn(&mut self) {
+        for idx in std::mem::take(&mut self.captured) {
+            self.cells[idx] = Square::empty();
         }
+
+        self.jumping = None;
+        self.current_turn = self.current_turn.opponent();
+    }
+
+    /// Get every sequence of hops that the piece at the given index can jump, empty if the piece can't jump
+    /// 
+    /// Each sequence is followed until the piece can't jump any further or the turn ends
+    pub fn jump_sequences(&self, from: BrdIdx) -> Vec<Vec<BrdIdx>> {
+        self.capture_sequences(from)
+            .into_iter()
+            .map(|capture| capture.hops)
+            .collect()
+    }
+
+    /// Get the most pieces that the current player can take this turn, 0 when there are no jumps
+    pub fn max_capture(&self) -> usize {
+        let pieces: Vec<BrdIdx> = match self.jumping {
+            Some(jumping) => vec![jumping],
+            None => PieceIterator::new(self)
+                .filter(|(_, square)| matches!(square.occupant, Some(piece) if piece.team == self.current_turn))
+                .map(|(idx, _)| self.board_index(idx))
+                .collect(),
+        };
+
+        pieces
+            .into_iter()
+            .flat_map(|idx| self.jump_sequences(idx))
+            .map(|hops| hops.len())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Get the 1D array indices for every square along the diagonals from a given board square, nearest first in each direction
+    /// 
+    /// # Returns
+    /// [`None`]: If the given square is unplayable
+    pub fn diagonal_indices(&self, idx: BrdIdx) -> Option<Vec<usize>> {
+        if self.cell_state(self.cell_idx(idx)) == Unplayable {
+            return None;
+        }
+
+        let mut cells = Vec::with_capacity(2 * usize::max(self.width, self.height));
+
+        for (row_step, col_step) in [(-1, -1), (-1, 1), (1, -1), (1, 1)] {
+            let mut row = idx.row as isize + row_step;
+            let mut col = idx.col as isize + col_step;
+
+            while row >= 0 && col >= 0 && row < self.height as isize && col < self.width as isize {
+                cells.push(self.cell_index(row as usize, col as usize));
+
+                row += row_step;
+                col += col_step;
+            }
+        }
+
+        cells.shrink_to_fit();
+        Some(cells)
+    }
+
+    /// Get the 1D array indices for the squares strictly between two squares on the same diagonal, empty if they aren't on a diagonal
+    pub fn between_indices(&self, from: BrdIdx, to: BrdIdx) -> Vec<usize> {
+        let (row_diff, col_diff) = Board::idx_diffs(from, to);
+
+        if row_diff.abs() != col_diff.abs() {
+            return Vec::new();
+        }
+
+        (1..row_diff.abs())
+            .map(|step| self.cell_index(
+                (from.row as isize + step * row_diff.signum()) as usize, 
+                (from.col as isize + step * col_diff.signum()) as usize,
+            ))
+            .collect()
+    }
+
+    /// Get the 1D array indices for every square that the piece at a given board square could possibly move to
+    /// 
+    /// This is every square along the diagonals for a flying king or the adjacent and jumpable squares otherwise
+    /// 
+    /// # Returns
+    /// [`None`]: If the given square is unplayable
+    pub fn destination_indices(&self, idx: BrdIdx) -> Option<Vec<usize>> {
+        let flying = self.rules.flying_kings && matches!(
+            self.cell(self.cell_idx(idx)).occupant, 
+            Some(Piece { strength: King, .. })
+        );
+
+        if flying {
+            return self.diagonal_indices(idx);
+        }
+
+        let mut cells = self.adjacent_indices(idx)?;
+        cells.append(&mut self.jumpable_indices(idx)?);
+        Some(cells)
     }
 
     /// Get the difference between two [`BrdIdx`] objects
@@ -395,13 +516,66 @@ impl Board {
         self.cells[idx]
     }
 
-    /// Get a copy of a board square by 1D array index
-    pub fn set_cell(&mut self, idx: usize, square: Square) {
-        // TODO: handle this error better?
+    /// Set a board square by 1D array index
+    /// 
+    /// Fails if the index is off the board or the square's state doesn't match its occupant, pieces can't be put on
+    /// unplayable squares
+    pub fn set_cell(&mut self, idx: usize, square: Square) -> Result<(), DraughtError> {
         if idx >= self.num_cells() {
-            panic!("Given index is too large, idx: {}, square: {:?}", idx, square);
+            return Err(DraughtError::CellOutOfBounds(idx));
         }
+
+        let playable = self.cells[idx].state != Unplayable;
+        let consistent = match square.state {
+            Empty => square.occupant.is_none() && playable,
+            Occupied => square.occupant.is_some() && playable,
+            Unplayable => square.occupant.is_none() && !playable,
+        };
+
+        if !consistent {
+            return Err(DraughtError::InconsistentSquare(self.board_index(idx)));
+        }
+
         self.cells[idx] = square;
+        Ok(())
+    }
+
+    /// Check that every square's state matches its occupant and that any piece part-way through a multi-jump is the
+    /// current player's
+    /// 
+    /// Boards built by the crate always pass, this catches positions put together by hand
+    pub fn check(&self) -> Result<(), DraughtError> {
+        if self.cells.len() != self.num_cells() {
+            return Err(DraughtError::CellOutOfBounds(self.cells.len()));
+        }
+
+        for (idx, square) in self.cells.iter().enumerate() {
+            let brd_idx = self.board_index(idx);
+            let playable = (brd_idx.row + brd_idx.col) % 2 == 1;
+
+            let consistent = match square.state {
+                Empty => square.occupant.is_none() && playable,
+                Occupied => square.occupant.is_some() && playable,
+                Unplayable => square.occupant.is_none() && !playable,
+            };
+
+            if !consistent {
+                return Err(DraughtError::InconsistentSquare(brd_idx));
+            }
+        }
+
+        if let Some(jumping) = self.jumping {
+            if jumping.row >= self.height || jumping.col >= self.width {
+                return Err(DraughtError::OutOfBounds(jumping));
+            }
+
+            match self.grid_cell(jumping).occupant {
+                Some(piece) if piece.team == self.current_turn => {},
+                _ => return Err(DraughtError::InvalidJumpingPiece(jumping)),
+            }
+        }
+
+        Ok(())
     }
 
     /// Get a copy of a board square by 2D [`BrdIdx`] index
@@ -427,7 +601,53 @@ impl Board {
     }
 
     /// Check whether a move given by source and destination indices is legal 
+    /// 
+    /// Includes the forced capture rule, a standard move is only allowed when the current player has no jumps available
     pub fn can_move(&self, from: BrdIdx, to: BrdIdx) -> Moveable {
+        // part-way through a multi-jump, only the jumping piece can move
+        if let Some(jumping) = self.jumping {
+            if jumping != from {
+                return Moveable::JumpInProgress;
+            }
+        }
+
+        let able = self.validate_move(from, to);
+
+        if able == Moveable::Allowed {
+            let jump = self.is_jump(from, to);
+
+            // standard move while a jump is available elsewhere on the board
+            if !jump && self.has_jump() {
+                return Moveable::CaptureRequired;
+            }
+
+            if jump {
+                let longest = self.jump_sequences(from)
+                    .into_iter()
+                    .filter(|hops| hops[0] == to)
+                    .map(|hops| hops.len())
+                    .max();
+
+                match longest {
+                    // flying king landing where it can't carry on when it could have elsewhere
+                    None => return Moveable::CaptureRequired,
+                    // jump that doesn't lead to taking as many pieces as possible
+                    Some(longest) => {
+                        if self.rules.capture_rule == CaptureRule::Majority && longest < self.max_capture() {
+                            return Moveable::MaximumCaptureRequired;
+                        }
+                    },
+                }
+            }
+        }
+
+        able
+    }
+
+    /// Check whether a move given by source and destination indices has a legal trajectory onto a free square
+    /// 
+    /// Unlike [`Board::can_move`], this does not consider the other moves available to the player 
+    pub fn validate_move(&self, from: BrdIdx, to: BrdIdx) -> Moveable {
 
         if from.row > self.height - 1 || from.col > self.width - 1 {
             return Moveable::OutOfBounds;
@@ -441,14 +661,14 @@ impl Board {
 
         // check source square is occupied
         match from_square.state {
-            Empty => return Moveable::UnoccupiedSrc,
-            Unplayable => return Moveable::Unplayable,
+            Empty => Moveable::UnoccupiedSrc,
+            Unplayable => Moveable::Unplayable,
             Occupied => {
 
                 // if its not the current teams piece then error
                 match from_square.occupant {
-                    // weird error, shouldn't happen
-                    None => panic!("Square is apparently occupied, but no occupant was found from: {}, to: {}, square: {:?}", from, to, from_square),
+                    // inconsistent square, treated as empty
+                    None => Moveable::UnoccupiedSrc,
                     Some(from_square_occupant) => {
 
                         // piece in the source square is not for the current turn's player
@@ -464,28 +684,19 @@ impl Board {
                         // but we catch it instead of returning to allow further checks on 
                         // the destination square
                         // TODO: refactor to a IsMove()/IsJump() to check whether the move has a legal trajectory
-                        match from_square_occupant.strength {
-                            Man => {
-                                let strength_check = self.validate_man_move(from, to, from_square_occupant);
-                                if strength_check != Moveable::Allowed {
-                                    return strength_check;
-                                }
-                            },
-                            King => {
-                                let strength_check = self.validate_king_move(from, to, from_square_occupant);
-                                if strength_check != Moveable::Allowed {
-                                    return strength_check;
-                                }
-                            },
+                        let strength_check = match from_square_occupant.strength {
+                            Man => self.validate_man_move(from, to, from_square_occupant),
+                            King => self.validate_king_move(from, to, from_square_occupant),
                         };
+                        if strength_check != Moveable::Allowed {
+                            return strength_check;
+                        }
 
                         let to_square = self.cell(self.cell_idx(to));
                         match to_square.state {
-                            Empty => {
-                                return Moveable::Allowed;
-                            },
-                            Unplayable => return Moveable::Unplayable,
-                            Occupied => return Moveable::OccupiedDest,
+                            Empty => Moveable::Allowed,
+                            Unplayable => Moveable::Unplayable,
+                            Occupied => Moveable::OccupiedDest,
                         }
                     }
                 }
@@ -493,6 +704,109 @@ impl Board {
         }
     }
 
+    /// Check whether the piece at the given index has a legal jump available
+    pub fn can_jump(&self, from: BrdIdx) -> bool {
+        match self.destination_indices(from) {
+            Some(destinations) => destinations
+                .into_iter()
+                .map(|i| self.board_index(i))
+                .any(|to| self.is_jump(from, to) && self.validate_move(from, to) == Moveable::Allowed),
+            None => false,
+        }
+    }
+
+    /// Get the number of a square in standard numeric notation, [`None`] if the square is unplayable or off the board
+    /// 
+    /// Playable squares are numbered from 1 along each row, starting from the bottom-right when viewed the way round that it's
+    /// drawn here. This puts Black on the low numbers and White on the high numbers as in PDN
+    pub fn square_number(&self, idx: BrdIdx) -> Option<usize> {
+        if idx.row >= self.height || idx.col >= self.width || self.grid_cell(idx).state == Unplayable {
+            return None;
+        }
+
+        let rows_below = (idx.row + 1..self.height)
+            .map(|row| self.row_playable_count(row))
+            .sum::<usize>();
+        let along_row = (idx.col..self.width)
+            .filter(|col| (idx.row + col) % 2 == 1)
+            .count();
+
+        Some(rows_below + along_row)
+    }
+
+    /// Get the board index for a square given in standard numeric notation, see [`Board::square_number`]
+    pub fn square_idx(&self, number: usize) -> Option<BrdIdx> {
+        if number == 0 {
+            return None;
+        }
+
+        let mut remaining = number;
+        for row in (0..self.height).rev() {
+            let in_row = self.row_playable_count(row);
+
+            if remaining > in_row {
+                remaining -= in_row;
+                continue;
+            }
+
+            return (0..self.width)
+                .rev()
+                .filter(|col| (row + col) % 2 == 1)
+                .nth(remaining - 1)
+                .map(|col| BrdIdx::from(row, col));
+        }
+
+        None
+    }
+
+    /// Number of playable squares in a row of the board
+    fn row_playable_count(&self, row: usize) -> usize {
+        (0..self.width).filter(|col| (row + col) % 2 == 1).count()
+    }
+
+    /// Check whether the current turn's player has any legal move available, a player without one has lost
+    pub fn has_legal_move(&self) -> bool {
+        if self.has_jump() {
+            return true;
+        }
+
+        PieceIterator::new(self).any(|(idx, square)| {
+            match square.occupant {
+                Some(piece) if piece.team == self.current_turn => {
+                    let from = self.board_index(idx);
+
+                    self.destination_indices(from)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .any(|i| self.validate_move(from, self.board_index(i)) == Moveable::Allowed)
+                },
+                _ => false,
+            }
+        })
+    }
+
+    /// Check whether moving between two squares would jump over a piece, 
+    /// i.e. they are at least two squares apart on a diagonal with an occupied square between them
+    pub fn is_jump(&self, from: BrdIdx, to: BrdIdx) -> bool {
+        self.between_indices(from, to)
+            .into_iter()
+            .any(|i| self.cell_state(i) == Occupied)
+    }
+
+    /// Check whether the current turn's player has any legal jump available, when they do they must take one
+    pub fn has_jump(&self) -> bool {
+        if let Some(jumping) = self.jumping {
+            return self.can_jump(jumping);
+        }
+
+        PieceIterator::new(self).any(|(idx, square)| {
+            match square.occupant {
+                Some(piece) => piece.team == self.current_turn && self.can_jump(self.board_index(idx)),
+                None => false,
+            }
+        })
+    }
+
     /// Check that given move trajectory is valid for a man piece 
     pub fn validate_man_move(&self, from: BrdIdx, to: BrdIdx, from_square_occupant: Piece) -> Moveable {
         let (row_diff, col_diff) = Board::idx_diffs(from, to);
@@ -507,15 +821,15 @@ impl Board {
         if row_diff == idx_scale {
             // destination is directly to the left or right
             if col_diff.abs() == 1 {
-                return Moveable::Allowed;
+                Moveable::Allowed
             } 
             // illegal, not adjacently diagional
             else {
-                return Moveable::IllegalTrajectory;
+                Moveable::IllegalTrajectory
             }
         }
-        // legal jump move trajectory
-        else if row_diff == 2 * idx_scale {
+        // legal jump move trajectory, backwards too if the rules allow it
+        else if row_diff == 2 * idx_scale || (self.rules.men_capture_backwards && row_diff == -2 * idx_scale) {
             // destination is directly to the left or right
             if col_diff.abs() == 2 {
 
@@ -523,38 +837,47 @@ impl Board {
                 let jumpee = self.cell(self.jumpee_idx(from, to));
                 match jumpee.state {
                     Empty => Moveable::NoJumpablePiece,
-                    Unplayable => panic!("Found an unplayable piece to try to jump over, from: {}, to: {}, jumpee: {:?}", from, to, jumpee),
+                    Unplayable => Moveable::NoJumpablePiece,
                     Occupied => {
 
+                        // pieces can't be jumped twice in one turn
+                        if self.captured.contains(&self.jumpee_idx(from, to)) {
+                            return Moveable::NoJumpablePiece;
+                        }
+
                         // check whether jumpee is an opponent's piece
-                        return Board::validate_jumpee(jumpee, from_square_occupant);
+                        Board::validate_jumpee(jumpee, from_square_occupant)
                     },
                 }
             } 
             // illegal, not adjacently diagional
             else {
-                return Moveable::IllegalTrajectory;
+                Moveable::IllegalTrajectory
             }
         }
         // illegal, not adjacently diagonal
         else {
-            return Moveable::IllegalTrajectory;
+            Moveable::IllegalTrajectory
         }
     }
 
     /// Check that given move trajectory is valid for a king piece 
     pub fn validate_king_move(&self, from: BrdIdx, to: BrdIdx, from_square_occupant: Piece) -> Moveable {
+        if self.rules.flying_kings {
+            return self.validate_flying_king_move(from, to, from_square_occupant);
+        }
+
         let (row_diff, col_diff) = Board::idx_diffs(from, to);
 
         // legal standard move 
         if row_diff.abs() == 1 {
             // destination is directly to the left or right
             if col_diff.abs() == 1 {
-                return Moveable::Allowed;
+                Moveable::Allowed
             } 
             // illegal, not adjacently diagional
             else {
-                return Moveable::IllegalTrajectory;
+                Moveable::IllegalTrajectory
             }
         }
         // legal jump move trajectory
@@ -566,27 +889,73 @@ impl Board {
                 let jumpee = self.cell(self.jumpee_idx(from, to));
                 match jumpee.state {
                     Empty => Moveable::NoJumpablePiece,
-                    Unplayable => panic!("Found an unplayable piece to try to jump over, from: {}, to: {}, jumpee: {:?}", from, to, jumpee),
+                    Unplayable => Moveable::NoJumpablePiece,
                     Occupied => {
 
+                        // pieces can't be jumped twice in one turn
+                        if self.captured.contains(&self.jumpee_idx(from, to)) {
+                            return Moveable::NoJumpablePiece;
+                        }
+
                         // check whether jumpee is an opponent's piece
-                        return Board::validate_jumpee(jumpee, from_square_occupant);
+                        Board::validate_jumpee(jumpee, from_square_occupant)
                     },
                 }
             } 
             // illegal, not adjacently diagional
             else {
-                return Moveable::IllegalTrajectory;
+                Moveable::IllegalTrajectory
             }
         }
         // illegal, not adjacently diagonal
         else {
+            Moveable::IllegalTrajectory
+        }
+    }
+
+    /// Check that given move trajectory is valid for a flying king, 
+    /// any distance along a diagonal either over empty squares or over a single opponent's piece
+    pub fn validate_flying_king_move(&self, from: BrdIdx, to: BrdIdx, from_square_occupant: Piece) -> Moveable {
+        let (row_diff, col_diff) = Board::idx_diffs(from, to);
+
+        // not diagonal
+        if row_diff.abs() != col_diff.abs() || row_diff == 0 {
             return Moveable::IllegalTrajectory;
         }
+
+        let occupied: Vec<usize> = self.between_indices(from, to)
+            .into_iter()
+            .filter(|i| self.cell_state(*i) == Occupied)
+            .collect();
+
+        match occupied.len() {
+            // clear diagonal, standard move
+            0 => Moveable::Allowed,
+            // single piece to jump over
+            1 => {
+                // pieces can't be jumped twice in one turn
+                if self.captured.contains(&occupied[0]) {
+                    return Moveable::NoJumpablePiece;
+                }
+
+                // check whether jumpee is an opponent's piece
+                Board::validate_jumpee(self.cell(occupied[0]), from_square_occupant)
+            },
+            // can't jump more than one piece at a time
+            _ => Moveable::IllegalTrajectory,
+        }
     }
 
     /// Get cell index of jumpee square given from and to locations
+    /// 
+    /// This is the first occupied square between the two, or the middle square when there isn't one
     pub fn jumpee_idx(&self, from: BrdIdx, to: BrdIdx) -> usize {
+        let between = self.between_indices(from, to);
+
+        if let Some(idx) = between.iter().find(|i| self.cell_state(**i) == Occupied) {
+            return *idx;
+        }
+
         let (row_diff, col_diff) = Board::idx_diffs(from, to);
         self.cell_idx(
             BrdIdx::from(
@@ -616,12 +985,10 @@ impl Board {
         let mut total = 0;
         for (_, square) in PieceIterator::new(self) {
             match square.occupant {
-                None => {},
-                Some(x) => {
-                    if x.team == team {
-                        total += 1;
-                    }
+                Some(x) if x.team == team => {
+                    total += 1;
                 },
+                _ => {},
             }
         }
         total
@@ -668,26 +1035,23 @@ impl Board {
         let to_idx = self.cell_idx(to);
 
         // make move update
-        new.set_cell(
-            to_idx, // destination square
-            self.cell(from_idx) // source piece
-        );
+        new.cells[to_idx] = self.cell(from_idx);
 
         // remove old piece
-        new.set_cell(
-            from_idx, // destination square
-            Square::empty() // empty piece
-        );
+        new.cells[from_idx] = Square::empty();
 
         Board::check_kinged(&mut new, to);
 
         // board has been changed, update player turn
-        new.current_turn = new.current_turn.opponent();
+        new.end_turn();
 
         new
     }
 
     /// Get new board derived from current with given jump applied
+    /// 
+    /// The turn only passes to the opponent once the jumping piece can't take any more pieces or has been kinged,
+    /// until then [`Board::jumping_piece`] gives the piece that has to carry on jumping
     pub fn apply_jump(&self, from: BrdIdx, to: BrdIdx) -> Board {
         let mut new = self.clone();
 
@@ -695,31 +1059,55 @@ impl Board {
         let to_idx = self.cell_idx(to);
 
         // make move update
-        new.set_cell(
-            to_idx, // destination square
-            self.cell(from_idx) // source piece
-        );
+        new.cells[to_idx] = self.cell(from_idx);
 
         // remove old piece
-        new.set_cell(
-            from_idx, // destination square
-            Square::empty() // empty piece
-        );
+        new.cells[from_idx] = Square::empty();
 
-        // remove jumpee
-        new.set_cell(
-            self.jumpee_idx(from, to), // destination square
-            Square::empty() // empty piece
-        );
+        // jumpee stays on the board until the end of the turn so that it can't be jumped twice
+        new.captured.push(self.jumpee_idx(from, to));
 
-        Board::check_kinged(&mut new, to);
+        // the same piece must keep jumping while it can
+        new.jumping = Some(to);
 
-        // board has been changed, update player turn
-        new.current_turn = new.current_turn.opponent();
+        match self.rules.promotion {
+            Promotion::EndsTurn => {
+                // reaching the king row ends the turn
+                let kinged = Board::check_kinged(&mut new, to);
+
+                if kinged || !new.can_jump(to) {
+                    new.end_turn();
+                }
+            },
+            Promotion::ContinuesAsKing => {
+                Board::check_kinged(&mut new, to);
+
+                if !new.can_jump(to) {
+                    new.end_turn();
+                }
+            },
+            Promotion::AtEndOfMove => {
+                // only crowned if the piece stops on the king row
+                if !new.can_jump(to) {
+                    Board::check_kinged(&mut new, to);
+                    new.end_turn();
+                }
+            },
+        }
 
         new
     }
 
+    /// Get the rules being played
+    pub fn rules(&self) -> Rules {
+        self.rules
+    }
+
+    /// Get the piece part-way through a multi-jump, [`None`] when a new turn is starting
+    pub fn jumping_piece(&self) -> Option<BrdIdx> {
+        self.jumping
+    }
+
     /// Get row index for current team, top row for black, bottom row for white
     pub fn king_row_idx(&self) -> usize {
         match self.current_turn {
@@ -739,13 +1127,13 @@ impl Board {
     pub fn validate_jumpee(jumpee: Square, from_occ: Piece) -> Moveable {
         // check whether jumpee is an opponent's piece
         match jumpee.occupant {
-            None => panic!("No occupant found when checking the from: {:?} , jumpee: {:?}", from_occ, jumpee),
+            None => Moveable::NoJumpablePiece,
             Some(jumpee_occupant_uw) => {
                 if Board::check_jumpee_team(from_occ, jumpee_occupant_uw) {
-                    return Moveable::Allowed;
+                    Moveable::Allowed
                 }
                 else {
-                    return Moveable::JumpingSameTeam;
+                    Moveable::JumpingSameTeam
                 }
             },
         }
@@ -753,23 +1141,21 @@ impl Board {
 
     /// Check that the source piece and the jumpee are of opposing teams
     pub fn check_jumpee_team(from: Piece, jumpee: Piece) -> bool {
-        return from.team.opponent() == jumpee.team
+        from.team.opponent() == jumpee.team
     }
 
-    /// Check and apply king strength
-    fn check_kinged(new_board: &mut Board, idx: BrdIdx) {
+    /// Check and apply king strength, returns whether a man was crowned
+    fn check_kinged(new_board: &mut Board, idx: BrdIdx) -> bool {
         if new_board.king_row_idx() == idx.row {
             let cell_idx = new_board.cell_idx(idx);
             let cell = new_board.cell(cell_idx);
-            match cell.occupant {
-                Some(piece) => {
-                    new_board.set_cell(cell_idx, Square::pc(piece.team, King));
-                },
-                None => {
-                    panic!("No piece found when checking king, idx: {}", idx);
-                },
+            if let Some(piece) = cell.occupant {
+                new_board.cells[cell_idx] = Square::pc(piece.team, King);
+                return piece.strength == Man;
             }
         }
+
+        false
     }
 
     /// Initialise a game board without game pieces
@@ -798,10 +1184,23 @@ impl Board {
             width,
             height,
 
-            current_turn
+            current_turn,
+            jumping: None,
+            captured: Vec::new(),
+            rules: Rules {
+                width, height,
+                ..Rules::default()
+            },
         }
     }
 
+    /// Initialise a game board without game pieces for the given rules
+    pub fn with_rules(rules: Rules, current_turn: Team) -> Board {
+        let mut board = Board::new(rules.width, rules.height, current_turn);
+        board.rules = rules;
+        board
+    }
+
     /// Reset the given board to a starting layout with 3 rows of opposing pieces
     pub fn init_game(board: Board, piece_rows: usize) -> Board {
         let mut new_board = board.clone();
@@ -837,7 +1236,11 @@ impl Display for Board {
 
                 let result = match self.cell_state(idx) {
                     Empty => write!(string, "_ "),
-                    Occupied => write!(string, "{} ", self.cell(idx).occupant.unwrap().team),
+                    Occupied => match self.cell(idx).occupant {
+                        Some(piece) => write!(string, "{} ", piece.team),
+                        // inconsistent square, see Board::check
+                        None => write!(string, "? "),
+                    },
                     Unplayable => write!(string, ". "),
                 };
                 if let Err(_err) = result {
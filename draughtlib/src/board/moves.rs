@@ -0,0 +1,157 @@
+//! Legal move generation for a [`Board`]
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+
+use super::{Board, BrdIdx};
+use super::enums::{Moveable, Strength};
+use super::iter::PieceIterator;
+use super::rules::CaptureRule;
+use crate::comp::Move;
+
+/// Capture sequence found from a square
+pub(super) struct Capture {
+    /// Landing square of each hop
+    pub(super) hops: Vec<BrdIdx>,
+    /// Square of each piece taken
+    pub(super) captured: Vec<BrdIdx>,
+    /// Whether the capturing piece is a king once the sequence is finished
+    pub(super) king: bool,
+}
+
+impl Board {
+    /// Get every capture sequence for the piece on the given square, see [`Board::jump_sequences`]
+    pub(super) fn capture_sequences(&self, from: BrdIdx) -> Vec<Capture> {
+        let mut hops = Vec::new();
+
+        if let Some(destinations) = self.destination_indices(from) {
+            for i in destinations {
+                let to = self.board_index(i);
+
+                if !self.is_jump(from, to) || self.validate_move(from, to) != Moveable::Allowed {
+                    continue;
+                }
+
+                hops.push((to, self.jumpee_idx(from, to), self.apply_jump(from, to)));
+            }
+        }
+
+        // flying kings have to land where they can carry on jumping if they can, 
+        // so drop the landing squares that finish the turn when another landing over the same piece doesn't
+        let continuing: Vec<usize> = hops
+            .iter()
+            .filter(|(to, _, next)| next.jumping_piece() == Some(*to))
+            .map(|(_, jumpee, _)| *jumpee)
+            .collect();
+
+        let mut sequences = Vec::new();
+
+        for (to, jumpee, next) in hops {
+            let jumpee_idx = self.board_index(jumpee);
+
+            // turn is over, this hop finishes the sequence
+            if next.jumping_piece() != Some(to) {
+                if !continuing.contains(&jumpee) {
+                    sequences.push(Capture {
+                        hops: vec![to],
+                        captured: vec![jumpee_idx],
+                        king: matches!(next.grid_cell(to).occupant, Some(piece) if piece.strength == Strength::King),
+                    });
+                }
+                continue;
+            }
+
+            // carry on jumping from the landing square
+            for mut rest in next.capture_sequences(to) {
+                rest.hops.insert(0, to);
+                rest.captured.insert(0, jumpee_idx);
+                sequences.push(rest);
+            }
+        }
+
+        sequences
+    }
+
+    /// Get capture moves for the piece on the given square
+    fn capture_moves(&self, from: BrdIdx) -> Vec<Move> {
+        let man = matches!(self.grid_cell(from).occupant, Some(piece) if piece.strength == Strength::Man);
+
+        self.capture_sequences(from)
+            .into_iter()
+            .map(|capture| Move::capture(from, capture.hops, capture.captured, man && capture.king))
+            .collect()
+    }
+
+    /// Filter capture sequences for those allowed by the board's [`CaptureRule`]
+    fn filter_captures(&self, jumps: Vec<Move>) -> Vec<Move> {
+        match self.rules.capture_rule {
+            CaptureRule::Free => jumps,
+            CaptureRule::Majority => {
+                let longest = jumps.iter().map(|m| m.hops().len()).max().unwrap_or(0);
+
+                jumps
+                    .into_iter()
+                    .filter(|m| m.hops().len() == longest)
+                    .collect()
+            },
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+impl Board {
+    /// Get every legal move for the current turn's player
+    /// 
+    /// Captures are compulsory, if any jumps are available then only the jumps are returned. 
+    /// Multi-jumps are returned as one move per possible sequence of hops
+    pub fn legal_moves(&self) -> Vec<Move> {
+
+        // part-way through a multi-jump, only the jumping piece can carry on
+        if let Some(jumping) = self.jumping {
+            return self.filter_captures(self.capture_moves(jumping));
+        }
+
+        // allocate capacity for 2 moves per piece, likely too much but will be shrunk
+        // to reduce memory re-allocations
+        let mut moves = Vec::with_capacity(self.num_player(self.current_turn) * 2);
+        let mut jumps = Vec::new();
+        let king_row = self.king_row_idx();
+
+        for (idx, square) in PieceIterator::new(self) {
+            let piece = match square.occupant {
+                Some(piece) if piece.team == self.current_turn => piece,
+                _ => continue,
+            };
+            let from = self.board_index(idx);
+
+            // iterate over reachable indices, any distance for flying kings
+            for i in self.destination_indices(from).unwrap_or_default() {
+                let to = self.board_index(i);
+
+                // check if can move, forced captures are handled below
+                if !self.is_jump(from, to) && self.validate_move(from, to) == Moveable::Allowed {
+                    moves.push(Move::step(from, to, piece.strength == Strength::Man && to.row == king_row));
+                }
+            }
+
+            // get every sequence of jumps from this piece
+            jumps.extend(self.capture_moves(from));
+        }
+
+        // a capture must be made when one is available
+        if !jumps.is_empty() {
+            return self.filter_captures(jumps);
+        }
+
+        moves.shrink_to_fit();
+        moves
+    }
+
+    /// Get the legal moves for the piece on the given square, empty when it can't move or another piece has to capture
+    pub fn legal_moves_from(&self, idx: BrdIdx) -> Vec<Move> {
+        self.legal_moves()
+            .into_iter()
+            .filter(|m| m.from() == idx)
+            .collect()
+    }
+}
@@ -0,0 +1,171 @@
+//! Rule sets for the different variants of draughts
+
+#[cfg(target_arch = "wasm32")]
+extern crate wasm_bindgen;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+
+use std::fmt::{Display};
+
+use super::enums::Team;
+
+/// Named variants of draughts with standard rule sets
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Variant {
+    /// English draughts/American checkers, 8x8 with short kings
+    English = 0,
+    /// International draughts, 10x10 with flying kings and majority capture
+    International = 1,
+    /// Russian draughts, 8x8 with flying kings, men are crowned part-way through a capture
+    Russian = 2,
+    /// Brazilian draughts, International rules on an 8x8 board
+    Brazilian = 3,
+    /// Pool checkers, 8x8 with flying kings and free choice of capture
+    Pool = 4,
+}
+
+impl Variant {
+    /// Get the standard rule set for the variant
+    pub fn rules(&self) -> Rules {
+        Rules::from_variant(*self)
+    }
+
+    /// Team that makes the first move in the variant
+    pub fn first_turn(&self) -> Team {
+        match self {
+            Variant::English | Variant::Pool => Team::Black,
+            Variant::International | Variant::Russian | Variant::Brazilian => Team::White,
+        }
+    }
+
+    /// Find the variant played by the given rules
+    /// 
+    /// Variants are matched on board size first then on the rules alone, [`None`] for rules not played by any variant
+    pub fn from_rules(rules: &Rules) -> Option<Variant> {
+        VARIANTS.iter()
+            .find(|v| v.rules() == *rules)
+            .or_else(|| VARIANTS.iter().find(|v| v.rules().same_play(rules)))
+            .copied()
+    }
+}
+
+/// Every named variant
+pub const VARIANTS: [Variant; 5] = [Variant::English, Variant::International, Variant::Russian, Variant::Brazilian, Variant::Pool];
+
+impl Display for Variant {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Variant::English => write!(f, "English"),
+            Variant::International => write!(f, "International"),
+            Variant::Russian => write!(f, "Russian"),
+            Variant::Brazilian => write!(f, "Brazilian"),
+            Variant::Pool => write!(f, "Pool"),
+        }
+    }
+}
+
+/// Which capture sequences a player can choose from
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum CaptureRule {
+    /// Any capture sequence can be taken
+    Free = 0,
+    /// The sequence taking the most pieces has to be taken
+    Majority = 1,
+}
+
+/// How a man reaching the far row part-way through a capture is treated
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Promotion {
+    /// Man is crowned and the turn ends
+    EndsTurn = 0,
+    /// Man is crowned and carries on capturing as a king
+    ContinuesAsKing = 1,
+    /// Man carries on capturing as a man, only crowned if the move finishes on the far row
+    AtEndOfMove = 2,
+}
+
+/// Rules for a game of draughts, used by a [`crate::Board`] when validating and applying moves
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Rules {
+    /// Number of columns on the board
+    pub width: usize,
+    /// Number of rows on the board
+    pub height: usize,
+    /// Number of rows of pieces each player starts with
+    pub piece_rows: usize,
+    /// Kings can move and capture any distance along a diagonal
+    pub flying_kings: bool,
+    /// Men can capture backwards as well as forwards
+    pub men_capture_backwards: bool,
+    /// Which of the available captures can be taken
+    pub capture_rule: CaptureRule,
+    /// Treatment of men reaching the far row during a capture
+    pub promotion: Promotion,
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+impl Rules {
+    /// Standard constructor function for a custom rule set
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen(constructor))]
+    pub fn new(width: usize, height: usize, piece_rows: usize, flying_kings: bool, men_capture_backwards: bool, capture_rule: CaptureRule, promotion: Promotion) -> Rules {
+        Rules {
+            width, height, piece_rows,
+            flying_kings, men_capture_backwards,
+            capture_rule, promotion,
+        }
+    }
+
+    /// Get the standard rule set for a variant
+    pub fn from_variant(variant: Variant) -> Rules {
+        match variant {
+            Variant::English => Rules::new(8, 8, 3, false, false, CaptureRule::Free, Promotion::EndsTurn),
+            Variant::International => Rules::new(10, 10, 4, true, true, CaptureRule::Majority, Promotion::AtEndOfMove),
+            Variant::Russian => Rules::new(8, 8, 3, true, true, CaptureRule::Free, Promotion::ContinuesAsKing),
+            Variant::Brazilian => Rules::new(8, 8, 3, true, true, CaptureRule::Majority, Promotion::AtEndOfMove),
+            Variant::Pool => Rules::new(8, 8, 3, true, true, CaptureRule::Free, Promotion::AtEndOfMove),
+        }
+    }
+
+    /// English rules on a board of the given size
+    pub fn sized(width: usize, height: usize, piece_rows: usize) -> Rules {
+        Rules {
+            width, height, piece_rows,
+            ..Rules::default()
+        }
+    }
+
+    /// Same rules played on a board of a different size
+    pub fn resized(&self, width: usize, height: usize, piece_rows: usize) -> Rules {
+        Rules {
+            width, height, piece_rows,
+            ..*self
+        }
+    }
+
+    /// Whether pieces move and capture the same way under both rule sets, ignoring the board size
+    pub fn same_play(&self, other: &Rules) -> bool {
+        self.flying_kings == other.flying_kings
+            && self.men_capture_backwards == other.men_capture_backwards
+            && self.capture_rule == other.capture_rule
+            && self.promotion == other.promotion
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Rules {
+        Rules::from_variant(Variant::English)
+    }
+}
@@ -1,4 +1,8 @@
 use super::*;
+use rules::{Rules, Variant};
+use fen::FenError;
+use bitboard::{BitBoard, BitBoardError};
+use crate::error::DraughtError;
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen_test::*;
 // use crate::log;
@@ -51,7 +55,7 @@ fn set_cell() {
     let mut board = Board::new(8, 8, Black);
     let square = Square::pc(White, Man);
 
-    board.set_cell(idx, square);
+    board.set_cell(idx, square).unwrap();
     assert_eq!(square, board.cell(idx));
 }
 
@@ -326,17 +330,17 @@ fn score() {
     //////////////////////////////////
     let mut board = Board::new(8, 8, Black);
     let square = Square::pc(Black, Man);
-    board.set_cell(1, square);
+    board.set_cell(1, square).unwrap();
     assert_eq!(1, board.score());
     //////////////////////////////////
 
     //////////////////////////////////
     let square = Square::pc(White, Man);
-    board.set_cell(5, square);
+    board.set_cell(5, square).unwrap();
     let square = Square::pc(Black, Man);
-    board.set_cell(7, square);
+    board.set_cell(7, square).unwrap();
     let square = Square::pc(Black, Man);
-    board.set_cell(8, square);
+    board.set_cell(8, square).unwrap();
     assert_eq!(2, board.score());
     //////////////////////////////////
 }
@@ -491,7 +495,7 @@ fn can_move_jump() {
     let board = Board::new(8, 8, White);
     let mut board = Board::init_game(board, 3);
 
-    board.set_cell(board.cell_index(3, 2), Square::pc(Black, Man));
+    board.set_cell(board.cell_index(3, 2), Square::pc(Black, Man)).unwrap();
 
     // log!("{}", board);
     // log!("{:?}", board.cell(board.cell_index(3, 2)));
@@ -501,7 +505,7 @@ fn can_move_jump() {
     let to = BrdIdx::from(4, 3);
     assert_eq!(board.can_move(from, to), Moveable::Allowed);
 
-    board.set_cell(board.cell_index(3, 2), Square::pc(White, Man));
+    board.set_cell(board.cell_index(3, 2), Square::pc(White, Man)).unwrap();
 
     let from = BrdIdx::from(2, 1);
     let to = BrdIdx::from(4, 3);
@@ -512,4 +516,642 @@ fn can_move_jump() {
     let to = BrdIdx::from(3, 2);
     assert_eq!(board.can_move(from, to), Moveable::OccupiedDest);
 
-}
\ No newline at end of file
+}
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn can_move_capture_required() {
+    let board = Board::new(8, 8, White);
+    let mut board = Board::init_game(board, 3);
+
+    assert!(!board.has_jump());
+
+    board.set_cell(board.cell_index(3, 2), Square::pc(Black, Man)).unwrap();
+
+    assert!(board.has_jump());
+    assert!(board.can_jump(BrdIdx::from(2, 1)));
+    assert!(!board.can_jump(BrdIdx::from(2, 5)));
+
+    // standard move elsewhere while a jump is available
+    let from = BrdIdx::from(2, 5);
+    let to = BrdIdx::from(3, 4);
+    assert_eq!(board.can_move(from, to), Moveable::CaptureRequired);
+    assert_eq!(board.validate_move(from, to), Moveable::Allowed);
+
+    // standard move with the piece that can jump
+    let from = BrdIdx::from(2, 1);
+    let to = BrdIdx::from(3, 0);
+    assert_eq!(board.can_move(from, to), Moveable::CaptureRequired);
+
+    // taking the jump is fine
+    let from = BrdIdx::from(2, 1);
+    let to = BrdIdx::from(4, 3);
+    assert_eq!(board.can_move(from, to), Moveable::Allowed);
+}
+
+///////////////////
+//  MULTI-JUMPS
+///////////////////
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn apply_jump_continues() {
+    let mut board = Board::new(8, 8, White);
+    board.set_cell(board.cell_index(1, 2), Square::pc(White, Man)).unwrap();
+    board.set_cell(board.cell_index(1, 6), Square::pc(White, Man)).unwrap();
+    board.set_cell(board.cell_index(2, 3), Square::pc(Black, Man)).unwrap();
+    board.set_cell(board.cell_index(4, 5), Square::pc(Black, Man)).unwrap();
+
+    let board = board.apply_jump(BrdIdx::from(1, 2), BrdIdx::from(3, 4));
+
+    // still white's go, the same piece has to keep jumping
+    assert_eq!(board.current_turn, White);
+    assert_eq!(board.jumping_piece(), Some(BrdIdx::from(3, 4)));
+    // taken piece stays until the end of the turn
+    assert_eq!(board.cell(board.cell_index(2, 3)), Square::pc(Black, Man));
+
+    assert_eq!(board.can_move(BrdIdx::from(1, 6), BrdIdx::from(2, 7)), Moveable::JumpInProgress);
+    assert_eq!(board.can_move(BrdIdx::from(3, 4), BrdIdx::from(4, 3)), Moveable::CaptureRequired);
+    assert_eq!(board.can_move(BrdIdx::from(3, 4), BrdIdx::from(1, 2)), Moveable::IllegalTrajectory);
+    assert_eq!(board.can_move(BrdIdx::from(3, 4), BrdIdx::from(5, 6)), Moveable::Allowed);
+
+    let board = board.apply_jump(BrdIdx::from(3, 4), BrdIdx::from(5, 6));
+
+    assert_eq!(board.current_turn, Black);
+    assert_eq!(board.jumping_piece(), None);
+    assert_eq!(board.num_player(Black), 0);
+    assert_eq!(board.cell(board.cell_index(5, 6)), Square::pc(White, Man));
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn apply_jump_kinged_ends_turn() {
+    let mut board = Board::new(8, 8, White);
+    board.set_cell(board.cell_index(5, 2), Square::pc(White, Man)).unwrap();
+    board.set_cell(board.cell_index(6, 3), Square::pc(Black, Man)).unwrap();
+    board.set_cell(board.cell_index(6, 5), Square::pc(Black, Man)).unwrap();
+
+    let board = board.apply_jump(BrdIdx::from(5, 2), BrdIdx::from(7, 4));
+
+    // new king could jump again but being crowned finishes the turn
+    assert_eq!(board.current_turn, Black);
+    assert_eq!(board.jumping_piece(), None);
+    assert_eq!(board.cell(board.cell_index(7, 4)), Square::pc(White, King));
+    assert_eq!(board.num_player(Black), 1);
+}
+
+///////////////
+//   RULES
+///////////////
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn men_capture_backwards() {
+    let mut board = Board::with_rules(Variant::Russian.rules(), White);
+    board.set_cell(board.cell_index(3, 2), Square::pc(White, Man)).unwrap();
+    board.set_cell(board.cell_index(2, 3), Square::pc(Black, Man)).unwrap();
+
+    assert_eq!(board.can_move(BrdIdx::from(3, 2), BrdIdx::from(1, 4)), Moveable::Allowed);
+    // still can't make a standard move backwards
+    assert_eq!(board.validate_move(BrdIdx::from(3, 2), BrdIdx::from(2, 1)), Moveable::IllegalTrajectory);
+
+    let mut board = Board::with_rules(Variant::English.rules(), White);
+    board.set_cell(board.cell_index(3, 2), Square::pc(White, Man)).unwrap();
+    board.set_cell(board.cell_index(2, 3), Square::pc(Black, Man)).unwrap();
+
+    assert_eq!(board.can_move(BrdIdx::from(3, 2), BrdIdx::from(1, 4)), Moveable::IllegalTrajectory);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn majority_capture() {
+    let mut board = Board::with_rules(Variant::International.rules(), White);
+    // can take 2
+    board.set_cell(board.cell_index(1, 2), Square::pc(White, Man)).unwrap();
+    board.set_cell(board.cell_index(2, 3), Square::pc(Black, Man)).unwrap();
+    board.set_cell(board.cell_index(4, 5), Square::pc(Black, Man)).unwrap();
+    // can take 1
+    board.set_cell(board.cell_index(7, 8), Square::pc(White, Man)).unwrap();
+    board.set_cell(board.cell_index(8, 7), Square::pc(Black, Man)).unwrap();
+
+    assert_eq!(board.max_capture(), 2);
+    assert_eq!(board.can_move(BrdIdx::from(7, 8), BrdIdx::from(9, 6)), Moveable::MaximumCaptureRequired);
+    assert_eq!(board.can_move(BrdIdx::from(1, 2), BrdIdx::from(3, 4)), Moveable::Allowed);
+
+    // free choice in english rules
+    let mut board = Board::with_rules(Rules::sized(10, 10, 4), White);
+    board.set_cell(board.cell_index(1, 2), Square::pc(White, Man)).unwrap();
+    board.set_cell(board.cell_index(2, 3), Square::pc(Black, Man)).unwrap();
+    board.set_cell(board.cell_index(4, 5), Square::pc(Black, Man)).unwrap();
+    board.set_cell(board.cell_index(7, 8), Square::pc(White, Man)).unwrap();
+    board.set_cell(board.cell_index(8, 7), Square::pc(Black, Man)).unwrap();
+
+    assert_eq!(board.can_move(BrdIdx::from(7, 8), BrdIdx::from(9, 6)), Moveable::Allowed);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn promotion_continues_as_king() {
+    let mut board = Board::with_rules(Variant::Russian.rules(), White);
+    board.set_cell(board.cell_index(5, 2), Square::pc(White, Man)).unwrap();
+    board.set_cell(board.cell_index(6, 3), Square::pc(Black, Man)).unwrap();
+    board.set_cell(board.cell_index(6, 5), Square::pc(Black, Man)).unwrap();
+
+    let board = board.apply_jump(BrdIdx::from(5, 2), BrdIdx::from(7, 4));
+
+    // crowned straight away and carries on as a king
+    assert_eq!(board.current_turn, White);
+    assert_eq!(board.jumping_piece(), Some(BrdIdx::from(7, 4)));
+    assert_eq!(board.cell(board.cell_index(7, 4)), Square::pc(White, King));
+
+    let board = board.apply_jump(BrdIdx::from(7, 4), BrdIdx::from(5, 6));
+
+    assert_eq!(board.current_turn, Black);
+    assert_eq!(board.num_player(Black), 0);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn promotion_at_end_of_move() {
+    let mut board = Board::with_rules(Variant::International.rules(), White);
+    board.set_cell(board.cell_index(7, 2), Square::pc(White, Man)).unwrap();
+    board.set_cell(board.cell_index(8, 3), Square::pc(Black, Man)).unwrap();
+    board.set_cell(board.cell_index(8, 5), Square::pc(Black, Man)).unwrap();
+
+    let board = board.apply_jump(BrdIdx::from(7, 2), BrdIdx::from(9, 4));
+
+    // passing through the king row doesn't crown the man
+    assert_eq!(board.jumping_piece(), Some(BrdIdx::from(9, 4)));
+    assert_eq!(board.cell(board.cell_index(9, 4)), Square::pc(White, Man));
+
+    let board = board.apply_jump(BrdIdx::from(9, 4), BrdIdx::from(7, 6));
+
+    assert_eq!(board.current_turn, Black);
+    assert_eq!(board.cell(board.cell_index(7, 6)), Square::pc(White, Man));
+
+    // stopping on the king row does
+    let mut board = Board::with_rules(Variant::International.rules(), White);
+    board.set_cell(board.cell_index(7, 2), Square::pc(White, Man)).unwrap();
+    board.set_cell(board.cell_index(8, 3), Square::pc(Black, Man)).unwrap();
+
+    let board = board.apply_jump(BrdIdx::from(7, 2), BrdIdx::from(9, 4));
+
+    assert_eq!(board.current_turn, Black);
+    assert_eq!(board.cell(board.cell_index(9, 4)), Square::pc(White, King));
+}
+
+////////////////////
+//  FLYING KINGS
+////////////////////
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn flying_king_moves() {
+    let mut board = Board::with_rules(Variant::Russian.rules(), White);
+    board.set_cell(board.cell_index(2, 1), Square::pc(White, King)).unwrap();
+
+    // any distance along a clear diagonal
+    assert_eq!(board.can_move(BrdIdx::from(2, 1), BrdIdx::from(6, 5)), Moveable::Allowed);
+    assert_eq!(board.can_move(BrdIdx::from(2, 1), BrdIdx::from(0, 3)), Moveable::Allowed);
+    assert!(!board.is_jump(BrdIdx::from(2, 1), BrdIdx::from(6, 5)));
+    // not diagonal
+    assert_eq!(board.can_move(BrdIdx::from(2, 1), BrdIdx::from(6, 3)), Moveable::IllegalTrajectory);
+
+    board.set_cell(board.cell_index(4, 3), Square::pc(Black, Man)).unwrap();
+
+    // capture from a distance landing anywhere past the piece
+    for landing in [BrdIdx::from(5, 4), BrdIdx::from(6, 5), BrdIdx::from(7, 6)] {
+        assert_eq!(board.can_move(BrdIdx::from(2, 1), landing), Moveable::Allowed);
+        assert!(board.is_jump(BrdIdx::from(2, 1), landing));
+        assert_eq!(board.jumpee_idx(BrdIdx::from(2, 1), landing), board.cell_index(4, 3));
+    }
+    assert_eq!(board.can_move(BrdIdx::from(2, 1), BrdIdx::from(4, 3)), Moveable::OccupiedDest);
+
+    // can't jump two pieces at once
+    board.set_cell(board.cell_index(5, 4), Square::pc(Black, Man)).unwrap();
+    assert_eq!(board.can_move(BrdIdx::from(2, 1), BrdIdx::from(6, 5)), Moveable::IllegalTrajectory);
+
+    // short kings in english rules
+    let mut board = Board::with_rules(Variant::English.rules(), White);
+    board.set_cell(board.cell_index(2, 1), Square::pc(White, King)).unwrap();
+    board.set_cell(board.cell_index(4, 3), Square::pc(Black, Man)).unwrap();
+
+    assert_eq!(board.can_move(BrdIdx::from(2, 1), BrdIdx::from(5, 4)), Moveable::IllegalTrajectory);
+    assert_eq!(board.diagonal_indices(BrdIdx::from(2, 1)).unwrap().len(), 9);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn flying_king_landing_must_continue() {
+    let mut board = Board::with_rules(Variant::Russian.rules(), White);
+    board.set_cell(board.cell_index(2, 1), Square::pc(White, King)).unwrap();
+    board.set_cell(board.cell_index(4, 3), Square::pc(Black, Man)).unwrap();
+    board.set_cell(board.cell_index(5, 6), Square::pc(Black, Man)).unwrap();
+
+    // only landing on (6, 5) lets the king take the second piece
+    assert_eq!(board.jump_sequences(BrdIdx::from(2, 1)), vec![vec![BrdIdx::from(6, 5), BrdIdx::from(4, 7)]]);
+    assert_eq!(board.can_move(BrdIdx::from(2, 1), BrdIdx::from(5, 4)), Moveable::CaptureRequired);
+    assert_eq!(board.can_move(BrdIdx::from(2, 1), BrdIdx::from(6, 5)), Moveable::Allowed);
+
+    let board = board.apply_jump(BrdIdx::from(2, 1), BrdIdx::from(6, 5));
+
+    // taken piece still blocks the diagonal
+    assert_eq!(board.can_move(BrdIdx::from(6, 5), BrdIdx::from(3, 2)), Moveable::NoJumpablePiece);
+
+    let board = board.apply_jump(BrdIdx::from(6, 5), BrdIdx::from(4, 7));
+
+    assert_eq!(board.current_turn, Black);
+    assert_eq!(board.num_player(Black), 0);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn square_numbers() {
+    let board = Board::new(8, 8, Black);
+
+    assert_eq!(board.square_number(BrdIdx::from(7, 6)), Some(1));
+    assert_eq!(board.square_number(BrdIdx::from(7, 0)), Some(4));
+    assert_eq!(board.square_number(BrdIdx::from(6, 7)), Some(5));
+    assert_eq!(board.square_number(BrdIdx::from(0, 1)), Some(32));
+    assert_eq!(board.square_number(BrdIdx::from(0, 0)), None);
+    assert_eq!(board.square_number(BrdIdx::from(8, 1)), None);
+
+    assert_eq!(board.square_idx(0), None);
+    assert_eq!(board.square_idx(33), None);
+
+    for number in 1..=32 {
+        let idx = board.square_idx(number).unwrap();
+        assert_eq!(board.square_number(idx), Some(number));
+    }
+
+    let board = Board::new(10, 10, White);
+    assert_eq!(board.square_idx(1), Some(BrdIdx::from(9, 8)));
+    assert_eq!(board.square_idx(50), Some(BrdIdx::from(0, 1)));
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn from_fen() {
+    let board = Board::from_fen("W:W21,22,K23:B1-3,K5", Rules::default()).unwrap();
+
+    assert_eq!(board.current_turn, White);
+    assert_eq!(board.num_player(White), 3);
+    assert_eq!(board.num_player(Black), 4);
+    assert_eq!(board.grid_cell(board.square_idx(21).unwrap()), Square::pc(White, Man));
+    assert_eq!(board.grid_cell(board.square_idx(23).unwrap()), Square::pc(White, King));
+    assert_eq!(board.grid_cell(board.square_idx(2).unwrap()), Square::pc(Black, Man));
+    assert_eq!(board.grid_cell(board.square_idx(5).unwrap()), Square::pc(Black, King));
+
+    // quotes, trailing full stop and any section order
+    let other = Board::from_fen("\"W:B1,2,3,K5:W21,22,K23.\"", Rules::default()).unwrap();
+    assert_eq!(board, other);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn from_fen_errors() {
+    assert_eq!(Board::from_fen("X:W21:B1", Rules::default()), Err(FenError::InvalidTurn("X".to_string())));
+    assert_eq!(Board::from_fen("W:X21:B1", Rules::default()), Err(FenError::InvalidTeam("X21".to_string())));
+    assert_eq!(Board::from_fen("W:W21:W1", Rules::default()), Err(FenError::DuplicateTeam(White)));
+    assert_eq!(Board::from_fen("W:W2a:B1", Rules::default()), Err(FenError::InvalidSquare("2a".to_string())));
+    assert_eq!(Board::from_fen("W:W33:B1", Rules::default()), Err(FenError::SquareOutOfRange(33)));
+    assert_eq!(Board::from_fen("W:W1:B1", Rules::default()), Err(FenError::DuplicateSquare(1)));
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn to_fen() {
+    let board = Board::init_game(Board::new(8, 8, Black), 3);
+    assert_eq!(board.to_fen(), "B:W21,22,23,24,25,26,27,28,29,30,31,32:B1,2,3,4,5,6,7,8,9,10,11,12");
+
+    let mut board = Board::new(8, 8, White);
+    board.set_cell(board.cell_idx(board.square_idx(23).unwrap()), Square::pc(White, King)).unwrap();
+    board.set_cell(board.cell_idx(board.square_idx(9).unwrap()), Square::pc(Black, Man)).unwrap();
+    assert_eq!(board.to_fen(), "W:WK23:B9");
+
+    let international = Board::init_game(Board::with_rules(Variant::International.rules(), White), 4);
+    assert_eq!(Board::from_fen(&international.to_fen(), Variant::International.rules()), Ok(international));
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn zobrist_transpositions() {
+    let start = Board::init_game(Board::new(8, 8, Black), 3);
+
+    // same position reached by two move orders
+    let first = start
+        .apply_move(BrdIdx::from(5, 0), BrdIdx::from(4, 1))
+        .apply_move(BrdIdx::from(2, 1), BrdIdx::from(3, 0))
+        .apply_move(BrdIdx::from(5, 6), BrdIdx::from(4, 7));
+    let second = start
+        .apply_move(BrdIdx::from(5, 6), BrdIdx::from(4, 7))
+        .apply_move(BrdIdx::from(2, 1), BrdIdx::from(3, 0))
+        .apply_move(BrdIdx::from(5, 0), BrdIdx::from(4, 1));
+
+    assert_eq!(first.zobrist_hash(), second.zobrist_hash());
+    assert_ne!(first.zobrist_hash(), start.zobrist_hash());
+
+    // hashes don't change between runs
+    assert_eq!(start.zobrist_hash(), Board::init_game(Board::new(8, 8, Black), 3).zobrist_hash());
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn zobrist_differences() {
+    let board = Board::from_fen("W:W21,K26:B1,5", Rules::default()).unwrap();
+
+    let black_to_move = Board::from_fen("B:W21,K26:B1,5", Rules::default()).unwrap();
+    let kinged = Board::from_fen("W:W21,K26:B1,K5", Rules::default()).unwrap();
+    let swapped = Board::from_fen("W:B21,K26:W1,5", Rules::default()).unwrap();
+
+    assert_ne!(board.zobrist_hash(), black_to_move.zobrist_hash());
+    assert_ne!(board.zobrist_hash(), kinged.zobrist_hash());
+    assert_ne!(board.zobrist_hash(), swapped.zobrist_hash());
+
+    // part-way through a multi-jump isn't the same as the position after it
+    let brd = Board::from_fen("B:W16,23,32:B12", Rules::default()).unwrap();
+    let mid_jump = brd.apply_jump(BrdIdx::from(5, 0), BrdIdx::from(3, 2));
+    let mut without_jump = mid_jump.clone();
+    without_jump.jumping = None;
+    without_jump.captured.clear();
+
+    assert_ne!(mid_jump.zobrist_hash(), without_jump.zobrist_hash());
+}
+
+/////////////////
+// BITBOARDS
+/////////////////
+
+/// Starting board of a variant, shared with the tests of other modules
+pub(crate) fn variant_start(variant: Variant) -> Board {
+    let rules = variant.rules();
+    Board::init_game(Board::with_rules(rules, variant.first_turn()), rules.piece_rows)
+}
+
+fn sorted_moves(mut moves: Vec<crate::comp::Move>) -> Vec<String> {
+    let mut moves: Vec<String> = moves.drain(..).map(|m| format!("{:?}", m)).collect();
+    moves.sort();
+    moves
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn bitboard_layout() {
+    for (rules, squares) in [(Variant::English.rules(), 32), (Variant::International.rules(), 50), (Rules::sized(6, 7, 2), 21)] {
+        let bitboard = BitBoard::new(rules, White).unwrap();
+        assert_eq!(bitboard.playable().count_ones(), squares);
+
+        for row in 0..rules.height {
+            for col in 0..rules.width {
+                let idx = BrdIdx::from(row, col);
+
+                match bitboard.bit(idx) {
+                    Some(bit) => {
+                        assert_eq!(bitboard.square(bit), idx);
+                        assert_ne!(bitboard.playable() & (1 << bit), 0);
+                    },
+                    None => assert_eq!((row + col) % 2, 0),
+                }
+            }
+        }
+    }
+
+    // 8x8 fits in 35 bits, 10x10 in 54
+    assert_eq!(64 - BitBoard::new(Variant::English.rules(), White).unwrap().playable().leading_zeros(), 35);
+    assert_eq!(64 - BitBoard::new(Variant::International.rules(), White).unwrap().playable().leading_zeros(), 54);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn bitboard_round_trip() {
+    for variant in rules::VARIANTS {
+        let board = variant_start(variant);
+        let bitboard = BitBoard::from_board(&board).unwrap();
+
+        assert_eq!(bitboard.to_board(), board);
+        assert_eq!(bitboard.score(), board.score());
+        assert_eq!(bitboard.num_player(White), board.num_player(White));
+        assert_eq!(bitboard.num_player(Black), board.num_player(Black));
+    }
+
+    let board = Board::from_fen("B:W21,22,K23:B1-3,K32", Rules::default()).unwrap();
+    let bitboard = BitBoard::from_board(&board).unwrap();
+    assert_eq!(bitboard.to_board(), board);
+    assert_eq!(bitboard.kings().count_ones(), 2);
+    assert_eq!(bitboard.score(), board.score());
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn bitboard_errors() {
+    assert_eq!(BitBoard::new(Rules::sized(7, 8, 3), White), Err(BitBoardError::OddWidth(7)));
+    assert_eq!(BitBoard::new(Rules::sized(12, 12, 4), White), Err(BitBoardError::TooLarge(12, 12)));
+
+    let board = Board::from_fen("B:W16,23,32:B12", Rules::default()).unwrap();
+    let mid_jump = board.apply_jump(BrdIdx::from(5, 0), BrdIdx::from(3, 2));
+    assert_eq!(BitBoard::from_board(&mid_jump), Err(BitBoardError::JumpInProgress));
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn bitboard_start_moves() {
+    let bitboard = BitBoard::from_board(&variant_start(Variant::English)).unwrap();
+    let moves = bitboard.moves();
+
+    assert_eq!(moves.len(), 7);
+    assert!(moves.iter().all(|m| !m.is_capture() && !m.promotes()));
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn bitboard_moves_match_board() {
+    use rand::prelude::*;
+    use crate::comp::Computer;
+
+    let comp = Computer::new(1, White, 1.0);
+
+    for variant in rules::VARIANTS {
+        for seed in 0..4 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut board = variant_start(variant);
+
+            for _ in 0..150 {
+                let bitboard = BitBoard::from_board(&board).unwrap();
+                let bit_moves = bitboard.moves();
+                let moves = comp.available_turns(&board);
+
+                assert_eq!(
+                    sorted_moves(bit_moves.iter().map(|m| bitboard.to_move(m)).collect()),
+                    sorted_moves(moves),
+                    "{} moves differ for {}", variant, board.to_fen()
+                );
+
+                let Some(mv) = bit_moves.choose(&mut rng) else {
+                    break;
+                };
+
+                let next = bitboard.to_move(mv).apply(&board);
+                assert_eq!(bitboard.apply(mv).to_board(), next, "{} boards differ after {:?}", variant, mv);
+                board = next;
+            }
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn bitboard_promotion_rules() {
+    let capture = |variant: Variant| {
+        let mut board = Board::with_rules(variant.rules(), Black);
+        board.set_cell(board.cell_index(2, 1), Square::pc(Black, Man)).unwrap();
+        board.set_cell(board.cell_index(1, 2), Square::pc(White, Man)).unwrap();
+        board.set_cell(board.cell_index(2, 5), Square::pc(White, Man)).unwrap();
+
+        BitBoard::from_board(&board).unwrap()
+    };
+
+    // crowned and carries on capturing as a flying king
+    let russian = capture(Variant::Russian);
+    let moves = russian.moves();
+    assert_eq!(moves.len(), 2);
+    for mv in &moves {
+        assert!(mv.promotes());
+        assert_eq!(mv.captured().count_ones(), 2);
+        assert_eq!(russian.square(mv.hops()[0]), BrdIdx::from(0, 3));
+
+        let after = russian.apply(mv);
+        assert_eq!(after.kings(), 1 << mv.to());
+        assert_eq!(after.num_player(White), 0);
+    }
+
+    // the turn ends on the king row, crowned in both cases
+    for variant in [Variant::English, Variant::Pool] {
+        let bitboard = capture(variant);
+        let moves = bitboard.moves();
+
+        assert_eq!(moves.len(), 1);
+        assert!(moves[0].promotes());
+        assert_eq!(moves[0].hops().len(), 1);
+        assert_eq!(bitboard.square(moves[0].to()), BrdIdx::from(0, 3));
+    }
+}
+
+/////////////////
+// LEGAL MOVES
+/////////////////
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn legal_moves_start() {
+    let board = variant_start(Variant::English);
+    let moves = board.legal_moves();
+
+    assert_eq!(moves.len(), 7);
+    assert!(moves.iter().all(|m| !m.is_capture() && !m.promotes() && m.captured().is_empty()));
+
+    // piece on the edge of the front row has one move, pieces behind it have none
+    assert_eq!(board.legal_moves_from(BrdIdx::from(5, 0)).len(), 1);
+    assert_eq!(board.legal_moves_from(BrdIdx::from(5, 2)).len(), 2);
+    assert!(board.legal_moves_from(BrdIdx::from(6, 1)).is_empty());
+    assert!(board.legal_moves_from(BrdIdx::from(4, 1)).is_empty());
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn legal_moves_captures() {
+    // black man on 12 has to take 16 and 23, the man on 32 can't move instead
+    let board = Board::from_fen("B:W16,23:B12,32", Rules::default()).unwrap();
+    let moves = board.legal_moves();
+
+    assert_eq!(moves.len(), 1);
+    assert_eq!(moves[0].from(), BrdIdx::from(5, 0));
+    assert_eq!(moves[0].to(), BrdIdx::from(1, 4));
+    assert_eq!(moves[0].captured(), vec![BrdIdx::from(4, 1), BrdIdx::from(2, 3)]);
+    assert!(!moves[0].promotes());
+
+    assert_eq!(board.legal_moves_from(BrdIdx::from(5, 0)), moves);
+    assert!(board.legal_moves_from(BrdIdx::from(0, 1)).is_empty());
+
+    // only the jumping piece can carry on part-way through
+    let mid_jump = board.apply_jump(BrdIdx::from(5, 0), BrdIdx::from(3, 2));
+    let rest = mid_jump.legal_moves();
+    assert_eq!(rest.len(), 1);
+    assert_eq!(rest[0].captured(), vec![BrdIdx::from(2, 3)]);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn legal_moves_promotion() {
+    let board = Board::from_fen("B:W32:B25,K26", Rules::default()).unwrap();
+
+    for mv in board.legal_moves() {
+        // men reaching the far row are crowned, kings aren't crowned again
+        assert_eq!(mv.promotes(), mv.from() == BrdIdx::from(1, 6));
+    }
+
+    // crowned part-way through a capture and carries on as a king
+    let mut board = Board::with_rules(Variant::Russian.rules(), Black);
+    board.set_cell(board.cell_index(2, 1), Square::pc(Black, Man)).unwrap();
+    board.set_cell(board.cell_index(1, 2), Square::pc(White, Man)).unwrap();
+    board.set_cell(board.cell_index(2, 5), Square::pc(White, Man)).unwrap();
+
+    let moves = board.legal_moves();
+    assert_eq!(moves.len(), 2);
+    for mv in moves {
+        assert!(mv.promotes());
+        assert_eq!(mv.captured(), vec![BrdIdx::from(1, 2), BrdIdx::from(2, 5)]);
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn set_cell_errors() {
+    let mut board = Board::new(8, 8, Black);
+
+    assert_eq!(board.set_cell(64, Square::empty()), Err(DraughtError::CellOutOfBounds(64)));
+    // pieces only go on playable squares
+    assert_eq!(board.set_cell(0, Square::pc(Black, Man)), Err(DraughtError::InconsistentSquare(BrdIdx::from(0, 0))));
+    assert_eq!(board.set_cell(1, Square::unplay()), Err(DraughtError::InconsistentSquare(BrdIdx::from(0, 1))));
+    // state has to match the occupant
+    assert_eq!(board.set_cell(1, Square::new(Occupied, None)), Err(DraughtError::InconsistentSquare(BrdIdx::from(0, 1))));
+    assert_eq!(board.set_cell(1, Square::new(Empty, Some(Piece::new(White, Man)))), Err(DraughtError::InconsistentSquare(BrdIdx::from(0, 1))));
+
+    assert_eq!(board.set_cell(1, Square::pc(White, Man)), Ok(()));
+    assert_eq!(board.cell(1), Square::pc(White, Man));
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn check_inconsistent_board() {
+    let mut board = Board::init_game(Board::new(8, 8, Black), 3);
+    assert_eq!(board.check(), Ok(()));
+
+    // occupied without a piece can't be moved or jumped, rather than panicking
+    let idx = BrdIdx::from(5, 0);
+    let cell_idx = board.cell_idx(idx);
+    board.cells[cell_idx] = Square::new(Occupied, None);
+    assert_eq!(board.check(), Err(DraughtError::InconsistentSquare(idx)));
+    assert_eq!(board.validate_move(idx, BrdIdx::from(4, 1)), Moveable::UnoccupiedSrc);
+    assert!(board.to_string().contains('?'));
+    assert_eq!(Board::validate_jumpee(board.grid_cell(idx), Piece::new(White, Man)), Moveable::NoJumpablePiece);
+
+    let mut board = Board::init_game(Board::new(8, 8, Black), 3);
+    board.jumping = Some(BrdIdx::from(0, 1));
+    assert_eq!(board.check(), Err(DraughtError::InvalidJumpingPiece(BrdIdx::from(0, 1))));
+}
+
+#[cfg(feature = "serde")]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn board_serde() {
+    let board = Board::init_game(Board::new(8, 8, Black), 3);
+    let board = board.apply_move(BrdIdx::from(5, 0), BrdIdx::from(4, 1));
+
+    let json = serde_json::to_string(&board).unwrap();
+    assert_eq!(serde_json::from_str::<Board>(&json).unwrap(), board);
+
+    let bytes = bincode::serialize(&board).unwrap();
+    assert_eq!(bincode::deserialize::<Board>(&bytes).unwrap(), board);
+
+    assert_eq!(serde_json::to_string(&Square::pc(White, King)).unwrap(), r#"{"occupant":{"team":"White","strength":"King"},"state":"Occupied"}"#);
+    assert_eq!(serde_json::to_string(&BrdIdx::from(2, 3)).unwrap(), r#"{"row":2,"col":3}"#);
+}
@@ -0,0 +1,58 @@
+//! Zobrist hashing of [`Board`] positions
+//!
+//! Each piece on each square, the side to move and any multi-jump in progress has a fixed pseudo-random key, a board's
+//! hash is every applicable key XOR'd together. Keys are generated on demand so hashes are the same between runs
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+
+use super::Board;
+use super::enums::Team;
+
+/// Number of keys for each square, one for each piece type, a jumping piece and a captured piece
+const KEYS_PER_SQUARE: u64 = 6;
+/// Key offset for the piece part-way through a multi-jump
+const JUMPING_KEY: u64 = 4;
+/// Key offset for a piece captured earlier in a multi-jump
+const CAPTURED_KEY: u64 = 5;
+
+/// Get the key for a given number, mixed with SplitMix64
+fn key(n: u64) -> u64 {
+    let mut z = n.wrapping_add(1).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Key for something on the given square
+fn square_key(cell_idx: usize, offset: u64) -> u64 {
+    // keep 0 free for the side to move
+    key(1 + cell_idx as u64 * KEYS_PER_SQUARE + offset)
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+impl Board {
+    /// Get the Zobrist hash of the position, boards with the same pieces, player to move and multi-jump state hash the same
+    pub fn zobrist_hash(&self) -> u64 {
+        let mut hash = match self.current_turn {
+            Team::Black => key(0),
+            Team::White => 0,
+        };
+
+        for (idx, square) in self.cells.iter().enumerate() {
+            if let Some(piece) = square.occupant {
+                hash ^= square_key(idx, piece.team as u64 * 2 + piece.strength as u64);
+            }
+        }
+
+        if let Some(jumping) = self.jumping {
+            hash ^= square_key(self.cell_idx(jumping), JUMPING_KEY);
+        }
+
+        for captured in &self.captured {
+            hash ^= square_key(*captured, CAPTURED_KEY);
+        }
+
+        hash
+    }
+}
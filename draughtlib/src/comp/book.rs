@@ -0,0 +1,191 @@
+//! Opening books of moves for the [`Computer`] to play instead of searching
+//!
+//! Positions are keyed by [`Board::zobrist_hash`] and hold the positions that can follow them, each with a weight that's
+//! usually the number of games it was played in. Book moves are picked at random in proportion to their weight.
+//!
+//! Books are written as text with one move per line, the hash of the position and of the position after the move in hex
+//! followed by the weight, `0123456789abcdef fedcba9876543210 12`. Anything after a `#` is a comment
+//!
+//! [`Computer`]: super::Computer
+
+use std::collections::HashMap;
+use std::fmt::{Display};
+use std::str::FromStr;
+
+use rand::Rng;
+use rand::seq::SliceRandom;
+
+use crate::board::Board;
+use crate::game::Game;
+use crate::pdn::{PdnGame, PdnError};
+
+use super::Move;
+
+/// Default number of turns from the start of each game to add to a book
+pub const DEFAULT_BOOK_PLIES: usize = 20;
+
+/// Problems reading an opening book or building one from PDN
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BookError {
+    /// Line isn't two hashes and a weight, `line` counts from 1
+    InvalidLine { line: usize, text: String },
+    /// Game in the PDN couldn't be read or replayed
+    Pdn(PdnError),
+}
+
+impl From<PdnError> for BookError {
+    fn from(e: PdnError) -> BookError {
+        BookError::Pdn(e)
+    }
+}
+
+impl Display for BookError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            BookError::InvalidLine { line, text } => write!(f, "Invalid book entry on line {}: {}", line, text),
+            BookError::Pdn(e) => write!(f, "Invalid PDN: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for BookError {}
+
+/// Weighted moves for positions, see the [module docs](self)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OpeningBook {
+    /// Hashes of the positions that can follow each position, with their weights
+    positions: HashMap<u64, Vec<(u64, u32)>>,
+}
+
+impl OpeningBook {
+    /// Create an empty book
+    pub fn new() -> OpeningBook {
+        OpeningBook::default()
+    }
+
+    /// Build a book from the first `max_plies` turns of every game in a PDN collection
+    pub fn from_pdn(pdn: &str, max_plies: usize) -> Result<OpeningBook, BookError> {
+        let mut book = OpeningBook::new();
+
+        for pdn_game in PdnGame::parse_all(pdn)? {
+            book.add_game(&pdn_game.replay(0)?, max_plies);
+        }
+
+        Ok(book)
+    }
+
+    /// Number of positions with book moves
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Whether the book has no moves
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Add a move from a position to the book, adding to its weight if it's already there
+    pub fn add(&mut self, position: &Board, next: &Board, weight: u32) {
+        self.add_hashes(position.zobrist_hash(), next.zobrist_hash(), weight);
+    }
+
+    fn add_hashes(&mut self, position: u64, next: u64, weight: u32) {
+        let moves = self.positions.entry(position).or_default();
+
+        // kept sorted so that books with the same moves are equal
+        match moves.binary_search_by_key(&next, |(hash, _)| *hash) {
+            Ok(i) => moves[i].1 = moves[i].1.saturating_add(weight),
+            Err(i) => moves.insert(i, (next, weight)),
+        }
+    }
+
+    /// Add the first `max_plies` turns of a game to the book, each with a weight of 1
+    pub fn add_game(&mut self, game: &Game, max_plies: usize) {
+        // boards part-way through a multi-jump aren't the start of a turn
+        let turns: Vec<&Board> = (0..game.ply())
+            .map(|turn| game.previous_board(turn))
+            .chain(std::iter::once(game.current_board()))
+            .filter(|board| board.jumping_piece().is_none())
+            .collect();
+
+        for pair in turns.windows(2).take(max_plies) {
+            self.add(pair[0], pair[1], 1);
+        }
+    }
+
+    /// Get the legal moves from a board that are in the book, with their weights
+    ///
+    /// Only legal moves are returned so a hash collision can't lead to an illegal move
+    pub fn moves(&self, board: &Board) -> Vec<(Move, u32)> {
+        let Some(book_moves) = self.positions.get(&board.zobrist_hash()) else {
+            return Vec::new();
+        };
+
+        board.legal_moves()
+            .into_iter()
+            .filter_map(|mv| {
+                let next = mv.apply(board).zobrist_hash();
+
+                book_moves.iter()
+                    .find(|(hash, _)| *hash == next)
+                    .map(|(_, weight)| (mv, *weight))
+            })
+            .filter(|(_, weight)| *weight > 0)
+            .collect()
+    }
+
+    /// Pick a book move for a board at random in proportion to the weights, [`None`] when the position isn't in the book
+    pub fn choose<R: Rng>(&self, board: &Board, rng: &mut R) -> Option<Move> {
+        self.moves(board)
+            .choose_weighted(rng, |(_, weight)| *weight)
+            .ok()
+            .map(|(mv, _)| mv.clone())
+    }
+}
+
+impl FromStr for OpeningBook {
+    type Err = BookError;
+
+    fn from_str(s: &str) -> Result<OpeningBook, BookError> {
+        let mut book = OpeningBook::new();
+
+        for (i, text) in s.lines().enumerate() {
+            let entry = text.split('#').next().unwrap_or_default().trim();
+            if entry.is_empty() {
+                continue;
+            }
+
+            let invalid = || BookError::InvalidLine { line: i + 1, text: text.to_string() };
+            let fields: Vec<&str> = entry.split_whitespace().collect();
+
+            let [position, next, weight] = fields[..] else {
+                return Err(invalid());
+            };
+
+            book.add_hashes(
+                u64::from_str_radix(position, 16).map_err(|_| invalid())?,
+                u64::from_str_radix(next, 16).map_err(|_| invalid())?,
+                weight.parse().map_err(|_| invalid())?,
+            );
+        }
+
+        Ok(book)
+    }
+}
+
+impl Display for OpeningBook {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        // sorted so that the same book is always written the same way
+        let mut entries: Vec<(u64, u64, u32)> = self.positions
+            .iter()
+            .flat_map(|(position, moves)| moves.iter().map(|(next, weight)| (*position, *next, *weight)))
+            .collect();
+        entries.sort_unstable();
+
+        for (position, next, weight) in entries {
+            writeln!(f, "{:016x} {:016x} {}", position, next, weight)?;
+        }
+
+        Ok(())
+    }
+}
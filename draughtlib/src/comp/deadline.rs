@@ -0,0 +1,44 @@
+//! Wall-clock deadlines for time-limited searches, using the browser's clock when built for the web
+
+use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+
+/// Point in time that a search has to finish by
+#[derive(Clone, Copy, Debug)]
+pub struct Deadline {
+    #[cfg(not(target_arch = "wasm32"))]
+    end: Instant,
+    /// Milliseconds since the epoch, [`Instant`] isn't available in the browser
+    #[cfg(target_arch = "wasm32")]
+    end: f64,
+}
+
+impl Deadline {
+    /// Deadline the given duration from now
+    pub fn after(duration: Duration) -> Deadline {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            Deadline { end: Instant::now() + duration }
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            Deadline { end: js_sys::Date::now() + duration.as_secs_f64() * 1000. }
+        }
+    }
+
+    /// Whether the deadline has been reached
+    pub fn passed(&self) -> bool {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            Instant::now() >= self.end
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            js_sys::Date::now() >= self.end
+        }
+    }
+}
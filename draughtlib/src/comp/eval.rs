@@ -0,0 +1,210 @@
+//! Heuristics for scoring boards at the bottom of the AI's search
+//!
+//! Scores follow [`Board::score`], positive is good for Black and negative is good for White
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+
+use crate::board::{Board, BrdIdx};
+use crate::board::enums::{Moveable, Strength, Team};
+use crate::board::iter::PieceIterator;
+
+use Team::*;
+use Strength::*;
+
+/// Scores boards for the AI, see [`Material`] and [`WeightedEvaluator`]
+pub trait Evaluator {
+    /// Score a board, positive is good for Black and negative is good for White
+    fn evaluate(&self, board: &Board) -> isize;
+}
+
+/// Any function from a board to a score can be used as an evaluator
+impl<F: Fn(&Board) -> isize> Evaluator for F {
+    fn evaluate(&self, board: &Board) -> isize {
+        self(board)
+    }
+}
+
+/// Piece count only, men are worth 1 and kings 2 as in [`Board::score`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Material;
+
+impl Evaluator for Material {
+    fn evaluate(&self, board: &Board) -> isize {
+        board.score()
+    }
+}
+
+/// Weight for each term of a [`WeightedEvaluator`], 0 turns a term off
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Weights {
+    /// Value of each man
+    pub man: isize,
+    /// Value of each king
+    pub king: isize,
+    /// Per row that each man has moved towards being crowned
+    pub advancement: isize,
+    /// Per man left on its own back row, stopping the other player from crowning there
+    pub back_rank: isize,
+    /// Per piece in the middle of the board
+    pub centre: isize,
+    /// Per move available to a player
+    pub mobility: isize,
+    /// Per man with no opposing pieces between it and being crowned
+    pub runaway: isize,
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+impl Weights {
+    /// Standard constructor function for custom weights
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen(constructor))]
+    pub fn new(man: isize, king: isize, advancement: isize, back_rank: isize, centre: isize, mobility: isize, runaway: isize) -> Weights {
+        Weights {
+            man, king, advancement, back_rank, centre, mobility, runaway,
+        }
+    }
+
+    /// Piece count only, scaled to match the other presets
+    pub fn material() -> Weights {
+        Weights::new(100, 200, 0, 0, 0, 0, 0)
+    }
+
+    /// Mix of every term
+    pub fn balanced() -> Weights {
+        Weights::new(100, 250, 5, 10, 8, 2, 40)
+    }
+
+    /// Pushes men forward to crown them and keeps options open
+    pub fn aggressive() -> Weights {
+        Weights::new(100, 250, 12, 0, 5, 4, 60)
+    }
+
+    /// Holds the back row and the centre
+    pub fn defensive() -> Weights {
+        Weights::new(100, 250, 2, 25, 12, 1, 20)
+    }
+}
+
+impl Default for Weights {
+    fn default() -> Weights {
+        Weights::balanced()
+    }
+}
+
+/// Sum of weighted positional terms, each calculated for Black minus White
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WeightedEvaluator {
+    pub weights: Weights,
+}
+
+impl WeightedEvaluator {
+    pub fn new(weights: Weights) -> WeightedEvaluator {
+        WeightedEvaluator { weights }
+    }
+
+    /// Number of rows between a team's back row and the given row
+    fn rows_advanced(board: &Board, team: Team, row: usize) -> usize {
+        match team {
+            Black => board.height - 1 - row,
+            White => row,
+        }
+    }
+
+    /// Whether the given square is in the middle half of the board
+    fn is_centre(board: &Board, idx: BrdIdx) -> bool {
+        let (row_margin, col_margin) = (board.height / 4, board.width / 4);
+
+        idx.row >= row_margin && idx.row < board.height - row_margin
+            && idx.col >= col_margin && idx.col < board.width - col_margin
+    }
+
+    /// Whether a man has no opposing pieces in the widening cone of squares between it and its crowning row
+    fn is_runaway(board: &Board, idx: BrdIdx, team: Team) -> bool {
+        let ahead: Vec<usize> = match team {
+            Black => (0..idx.row).rev().collect(),
+            White => (idx.row + 1..board.height).collect(),
+        };
+
+        ahead.into_iter().enumerate().all(|(distance, row)| {
+            let spread = distance + 1;
+            let first_col = idx.col.saturating_sub(spread);
+            let last_col = (idx.col + spread).min(board.width - 1);
+
+            (first_col..=last_col).all(|col| !matches!(
+                board.grid_cell(BrdIdx::from(row, col)).occupant,
+                Some(piece) if piece.team != team
+            ))
+        })
+    }
+
+    /// Number of moves, including each first hop of a jump, that a team's pieces could make
+    fn mobility(board: &Board, team: Team) -> isize {
+        let mut board = board.clone();
+        board.current_turn = team;
+
+        PieceIterator::new(&board)
+            .filter(|(_, square)| matches!(square.occupant, Some(piece) if piece.team == team))
+            .map(|(idx, _)| {
+                let from = board.board_index(idx);
+
+                board.destination_indices(from)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|i| board.validate_move(from, board.board_index(*i)) == Moveable::Allowed)
+                    .count() as isize
+            })
+            .sum()
+    }
+}
+
+impl Evaluator for WeightedEvaluator {
+    fn evaluate(&self, board: &Board) -> isize {
+        let w = &self.weights;
+        let mut score = 0;
+
+        for (idx, square) in PieceIterator::new(board) {
+            let piece = match square.occupant {
+                Some(piece) => piece,
+                None => continue,
+            };
+            let brd_idx = board.board_index(idx);
+
+            let mut value = match piece.strength {
+                Man => w.man,
+                King => w.king,
+            };
+
+            if piece.strength == Man {
+                let advanced = WeightedEvaluator::rows_advanced(board, piece.team, brd_idx.row);
+                value += w.advancement * advanced as isize;
+
+                if advanced == 0 {
+                    value += w.back_rank;
+                }
+
+                if w.runaway != 0 && WeightedEvaluator::is_runaway(board, brd_idx, piece.team) {
+                    value += w.runaway;
+                }
+            }
+
+            if WeightedEvaluator::is_centre(board, brd_idx) {
+                value += w.centre;
+            }
+
+            match piece.team {
+                Black => score += value,
+                White => score -= value,
+            }
+        }
+
+        if w.mobility != 0 {
+            score += w.mobility * (WeightedEvaluator::mobility(board, Black) - WeightedEvaluator::mobility(board, White));
+        }
+
+        score
+    }
+}
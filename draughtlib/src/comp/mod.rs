@@ -1,46 +1,179 @@
 //! AI player logic
 
-use indextree::{Arena, Node, NodeId, NodeEdge};
-
 use rand::prelude::*;
 use rand::seq::SliceRandom;
 
 #[cfg(target_arch = "wasm32")]
 extern crate wasm_bindgen;
-// use wasm_bindgen::prelude::*;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
 
 // use draught_web::log;
 // use draught_web::log_error;
 
 use crate::board::{Board, BrdIdx};
-use crate::board::enums::{MoveType, Moveable, Team};
-use crate::board::iter::{PieceIterator};
+use crate::board::enums::{MoveType, Team};
+use crate::error::DraughtError;
 
 use Team::*;
-// use Strength::*;
 // use SquareState::*;
 
 // use std::fmt::{Display, Write};
 
 #[cfg(test)] pub mod tests;
 
+pub mod deadline;
+use deadline::Deadline;
+
+pub mod transposition;
+use transposition::{TranspositionTable, Entry, Bound};
+
+pub mod eval;
+use eval::{Evaluator, Material};
+
+pub mod perft;
+
+pub mod book;
+use book::OpeningBook;
+
+pub mod tablebase;
+use tablebase::{Tablebase, Outcome};
+
+use std::sync::Arc;
+
+use std::time::Duration;
+
+/// Score for a board where a player has no moves left and has lost, kept well above any piece count
+pub const WIN_SCORE: isize = 1_000_000;
+
+/// Deepest search tried when searching for a length of time
+pub const MAX_SEARCH_DEPTH: usize = 64;
+
+/// How often the deadline is checked during a timed search, one less than a power of 2 so the node count can be masked
+const DEADLINE_CHECK_MASK: usize = 1023;
+
 /// Represents a move by source/destination indices and the move type
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// 
+/// Jumps hold the landing square of each hop so that a multi-jump is a single move
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Move {
     from: BrdIdx,
     to: BrdIdx,
     mv_type: MoveType,
+    /// Landing squares for each hop of a jump, the last is the destination
+    hops: Vec<BrdIdx>,
+    /// Squares of the pieces taken by a jump, in the order they're jumped
+    captured: Vec<BrdIdx>,
+    /// Whether a man is crowned by the move
+    promotes: bool,
 }
 
 impl Move {
+    /// Create a move without looking at a board, see [`Board::legal_moves`] for moves with their captures and promotion filled in
     pub fn new(from: BrdIdx, to: BrdIdx, mv_type: MoveType) -> Move {
+        let hops = match mv_type {
+            MoveType::Move => Vec::new(),
+            MoveType::Jump => vec![to],
+        };
+
         Move {
-            from, to, mv_type
+            from, to, mv_type, hops,
+            captured: Vec::new(),
+            promotes: false,
         }
     }
+
+    /// Create a jump move made up of a sequence of hops, given by each landing square
+    pub fn jumps(from: BrdIdx, hops: Vec<BrdIdx>) -> Result<Move, DraughtError> {
+        let to = *hops.last().ok_or(DraughtError::NoHops)?;
+
+        Ok(Move {
+            from, to, mv_type: MoveType::Jump, hops,
+            captured: Vec::new(),
+            promotes: false,
+        })
+    }
+
+    /// Create a simple move found by move generation
+    pub(crate) fn step(from: BrdIdx, to: BrdIdx, promotes: bool) -> Move {
+        Move {
+            promotes,
+            ..Move::new(from, to, MoveType::Move)
+        }
+    }
+
+    /// Create a jump found by move generation, with the squares of the pieces that it takes
+    pub(crate) fn capture(from: BrdIdx, hops: Vec<BrdIdx>, captured: Vec<BrdIdx>, promotes: bool) -> Move {
+        Move {
+            from,
+            to: hops.last().copied().unwrap_or(from),
+            mv_type: MoveType::Jump,
+            hops, captured, promotes,
+        }
+    }
+
+    /// Landing squares for each hop of a jump, empty for a simple move
+    pub fn hops(&self) -> &[BrdIdx] {
+        &self.hops
+    }
 }
 
-/// For storing boards in the AI tree, stores board with score for comparisons
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+impl Move {
+    /// Source square of the move
+    pub fn from(&self) -> BrdIdx {
+        self.from
+    }
+
+    /// Destination square of the move, the last landing square for a jump
+    pub fn to(&self) -> BrdIdx {
+        self.to
+    }
+
+    /// Whether the move is a simple move or a jump
+    pub fn mv_type(&self) -> MoveType {
+        self.mv_type
+    }
+
+    /// Whether the move takes any pieces
+    pub fn is_capture(&self) -> bool {
+        self.mv_type == MoveType::Jump
+    }
+
+    /// Squares of the pieces taken by a jump in the order they're jumped, only known for moves from [`Board::legal_moves`]
+    pub fn captured(&self) -> Vec<BrdIdx> {
+        self.captured.clone()
+    }
+
+    /// Whether a man is crowned by the move, only known for moves from [`Board::legal_moves`]
+    pub fn promotes(&self) -> bool {
+        self.promotes
+    }
+
+    /// Get a new board with the whole move applied, including every hop of a multi-jump
+    pub fn apply(&self, board: &Board) -> Board {
+        match self.mv_type {
+            MoveType::Move => board.apply_move(self.from, self.to),
+            MoveType::Jump => {
+                let mut from = self.from;
+                let mut new = board.clone();
+
+                for hop in &self.hops {
+                    new = new.apply_jump(from, *hop);
+                    from = *hop;
+                }
+
+                new
+            },
+        }
+    }
+}
+
+/// For storing boards alongside their searched score for comparisons
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct BoardNode {
     pub board: Board,
@@ -62,370 +195,427 @@ impl BoardNode {
 }
 
 /// Root-level structure for managing the game as a collection of board states
+/// 
+/// Boards at the bottom of the search are scored by the [`Evaluator`], piece count by default
 #[derive(Debug)]
-pub struct Computer {
+pub struct Computer<E = Material> {
     pub search_depth: usize,
     pub team: Team,
+    /// Number of boards scored during the last search
     pub last_node_count: usize,
+    /// Depth of moves looked ahead in the last search, the deepest completed iteration for a timed search
+    pub last_depth: usize,
     pub perfect_chance: f64,
+    /// End of the current timed search
+    deadline: Option<Deadline>,
+    /// Current search ran out of time and its scores can't be used
+    aborted: bool,
+    /// Current search stopped at the depth limit on at least one line, a deeper search could give different scores
+    depth_limited: bool,
+    /// Positions searched during the current search
+    table: TranspositionTable,
+    /// Scores boards at the bottom of the search
+    evaluator: E,
+    /// Moves to play without searching
+    book: Option<Arc<OpeningBook>>,
+    /// Solved endgames to play perfectly from and to score the search with
+    tablebase: Option<Arc<Tablebase>>,
+    /// Source of the random moves and of choices between equally good moves
+    rng: StdRng,
 }
 
 impl Computer {
     pub fn new(search_depth: usize, team: Team, perfect_chance: f64) -> Computer {
+        Computer::with_evaluator(search_depth, team, perfect_chance, Material)
+    }
+}
+
+impl<E: Evaluator> Computer<E> {
+    /// Create a computer scoring boards with the given evaluator
+    pub fn with_evaluator(search_depth: usize, team: Team, perfect_chance: f64, evaluator: E) -> Computer<E> {
         Computer {
             search_depth,
             team,
             perfect_chance,
             last_node_count: 0,
+            last_depth: 0,
+            deadline: None,
+            aborted: false,
+            depth_limited: false,
+            table: TranspositionTable::default(),
+            evaluator,
+            book: None,
+            tablebase: None,
+            rng: StdRng::from_entropy(),
         }
     }
 
-    /// Get vector of available moves for a given board
-    fn available_turns(&self, board: &Board) -> Vec<Move> {
-
-        // allocate capacity for 2 moves per piece, likely too much but will be shrunk
-        // to reduce memory re-allocations
-        let mut moves = Vec::with_capacity(board.num_player(board.current_turn) * 2);
-
-        // get all pieces in the board
-        for (idx, square) in PieceIterator::new(board) {
-
-            match square.occupant {
-                None => continue,
-                Some(piece) => {
-
-                    // filter for current team's pieces
-                    if piece.team == board.current_turn {
-                        let from_brd_idx = board.board_index(idx);
-                        let adj_op = board.adjacent_indices(from_brd_idx);
-                        let jump_op = board.jumpable_indices(from_brd_idx);
-
-                        // iterate over adjacent indices
-                        if let Some(adj) = adj_op {
-                            for i in adj {
-                                let to_brd_idx = board.board_index(i);
-
-                                // check if can move
-                                if board.can_move(from_brd_idx, to_brd_idx) == Moveable::Allowed {
-                                    moves.push(Move::new(from_brd_idx, to_brd_idx, MoveType::Move));
-                                }
-                            }
-                        } else {
-                            panic!("Unable to unwrap adjacent indices, from: {}, brd: {}", from_brd_idx, board);
-                        }
-
-                        // iterate over jumpable indices
-                        if let Some(jump) = jump_op {
-                            for i in jump {
-                                let to_brd_idx = board.board_index(i);
-
-                                // check if can move
-                                if board.can_move(from_brd_idx, to_brd_idx) == Moveable::Allowed {
-                                    moves.push(Move::new(from_brd_idx, to_brd_idx, MoveType::Jump));
-                                }
-                            }
-                        } else {
-                            panic!("Unable to unwrap adjacent indices, from: {}, brd: {}", from_brd_idx, board);
-                        }
-                    }
-                },
-            }
-        }
+    /// Evaluator used to score boards
+    pub fn evaluator(&self) -> &E {
+        &self.evaluator
+    }
 
-        moves.shrink_to_fit();
-        moves
+    /// Play moves from the given opening book when there are any for the position
+    pub fn set_book(&mut self, book: Arc<OpeningBook>) {
+        self.book = Some(book);
     }
 
-    /// Generate tree of boards to given search depth, return root node
-    fn gen_tree(&mut self, tree: &mut Arena<BoardNode>, board: Board) -> NodeId {
+    /// Opening book that moves are played from before searching
+    pub fn book(&self) -> Option<&OpeningBook> {
+        self.book.as_deref()
+    }
 
-        // root node of tree
-        let root = tree.new_node(BoardNode::brd(board));
-        
-        let mut nodes = vec!(root);
+    /// Make every random choice from a generator seeded with the given value, the same seed and board always give the
+    /// same move from [`Computer::get_move`]
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
 
-        for _ in 0..self.search_depth {
-            if nodes.len() == 0 {
-                break;
-            }
+    /// Play perfectly from positions in the given tablebase and use it to score positions found while searching
+    pub fn set_tablebase(&mut self, tablebase: Arc<Tablebase>) {
+        self.tablebase = Some(tablebase);
+    }
 
-            nodes = self.expand_layer(tree, nodes);
-        }
+    /// Endgame tablebase that moves are played from before searching
+    pub fn tablebase(&self) -> Option<&Tablebase> {
+        self.tablebase.as_deref()
+    }
 
-        root
-    }
-
-    /// Propagate scores up the tree employing MiniMax algorithm
-    fn propagate_scores(tree: Arena<BoardNode>, root: NodeId) -> Arena<BoardNode> {
-
-        // need to clone tree because we iterate over it and edit it at the same time
-        let mut new_tree = tree.clone();
-
-        for n in root.traverse(&tree) {
-            // only check end variant, checks nodes once
-            if let NodeEdge::End(node_id) = n {
-
-                // board current being looked at
-                let board_node = new_tree
-                    .get(node_id) // get Node
-                    .expect("No node returned for node id")
-                    .get(); // get BoardNode from Node
-                
-                // get scores of each nodes children
-                let children_scores: Vec<isize> = node_id // current node
-                    .children(&new_tree)
-                    .into_iter()
-                    .map(
-                        |n| new_tree
-                            .get(n) // get Node
-                            .expect("No node returned for node id") // unwrap, should always be fine
-                            .get() // get BoardNode from Node
-                            .score // get score from BoardNode
-                    )
-                    .collect(); // finalise
-
-                // only nodes which have children, ignores leaves
-                if children_scores.len() != 0 {
-                    
-                    // calculate score to propagate up tree
-                    new_tree
-                        .get_mut(node_id) // get mutable Node to change score on
-                        .expect("No node returned for node id") // should always be fine
-                        .get_mut() // get mutable BoardNode from Node
-                        .score = Computer::best_score(&board_node.board, children_scores);
-                }
-            }
-        }
+    /// Get vector of available moves for a given board, see [`Board::legal_moves`]
+    pub(crate) fn available_turns(&self, board: &Board) -> Vec<Move> {
+        board.legal_moves()
+    }
+
+    /// Sort moves so that those most likely to be best are searched first, letting alpha-beta cut off more of the tree
+    /// 
+    /// Longer captures come first then men being crowned
+    fn order_moves(moves: &mut [Move]) {
+        let priority = |m: &Move| m.hops.len() * 2 + m.promotes as usize;
+
+        moves.sort_by_key(|m| std::cmp::Reverse(priority(m)));
+    }
 
-        new_tree
-    }
-
-    /// Get best of given scores for given team
-    fn best_score(board: &Board, children_scores: Vec<isize>) -> isize {
-        match board.current_turn { // MiniMax algorithm here
-            // whether maximised or minimsed is based on current player
-            White => children_scores
-                .into_iter()
-                .min()
-                .expect("Couldn't unwrap min score value"),
-            Black => children_scores
-                .into_iter()
-                .max()
-                .expect("Couldn't unwrap max score value"),
+    /// Score for a board where the player to move has lost, wins found with more search depth remaining are sooner
+    fn loss_score(board: &Board, depth: usize) -> isize {
+        let score = WIN_SCORE + depth as isize;
+
+        match board.current_turn {
+            White => score,
+            Black => -score,
         }
     }
 
-    /// For given NodeIDs, insert score of board into tree
-    /// Used for leaf nodes ready for propagating up tree
-    fn insert_board_scores(&mut self, tree: &mut Arena<BoardNode>, nodes: Vec<NodeId>) {
-        for i in nodes.into_iter() {
-            match tree.get_mut(i) {
-                Some(node) => {
-                    let board_node = node.get_mut();
-                    board_node.score = board_node.board.score();
-                },
-                None => {
-                    panic!("Failed to get node when inserting board scores");
-                }
-            }
+    /// Score for a board in the tablebase, sooner wins score higher but always below a win found by searching
+    fn tablebase_score(&self, board: &Board) -> Option<isize> {
+        let probe = self.tablebase.as_ref()?.probe(board)?;
+
+        let score = match probe.outcome {
+            Outcome::Win => WIN_SCORE / 2 - probe.distance as isize,
+            Outcome::Loss => probe.distance as isize - WIN_SCORE / 2,
+            Outcome::Draw => 0,
+        };
+
+        match board.current_turn {
+            Black => Some(score),
+            White => Some(-score),
         }
     }
 
-    /// Traverse tree from given root ID and get NodeIDs of leaf nodes (those without children)
-    fn get_leaf_nodes(&mut self, tree: &mut Arena<BoardNode>, root: NodeId) -> Vec<NodeId> {
+    /// Score a board by searching the given depth of moves ahead with alpha-beta pruned MiniMax
+    /// 
+    /// Black maximises the score and White minimises it. Scores outside of the `(alpha, beta)` window are only bounds, 
+    /// the branch can't be reached with best play.
+    /// 
+    /// Positions found in the transposition table are only reused when searched to the same depth, keeping scores the
+    /// same as a fixed-depth search. The best move from any depth is searched first
+    fn alpha_beta(&mut self, board: &Board, depth: usize, mut alpha: isize, mut beta: isize) -> isize {
+        if self.aborted {
+            return 0;
+        }
 
-        let mut leaves = Vec::with_capacity(self.search_depth * 30);
+        self.last_node_count += 1;
 
-        for n in root.traverse(tree) {
+        if self.last_node_count & DEADLINE_CHECK_MASK == 0 && self.deadline.is_some_and(|d| d.passed()) {
+            self.aborted = true;
+            return 0;
+        }
 
-            // only check start variant, checks nodes once 
-            if let NodeEdge::Start(node_id) = n {
+        if let Some(score) = self.tablebase_score(board) {
+            return score;
+        }
 
-                let children: Vec<NodeId> = node_id.children(tree).collect();
+        if depth == 0 {
+            self.depth_limited = true;
+            return self.evaluator.evaluate(board);
+        }
 
-                if children.len() == 0 {
-                    leaves.push(node_id);
+        let hash = board.zobrist_hash();
+        let mut best_move = None;
+
+        if let Some(entry) = self.table.get(hash) {
+            best_move = entry.best_move;
+
+            if entry.depth == depth {
+                // can't tell whether the stored search reached the end of every line
+                self.depth_limited = true;
+
+                match entry.bound {
+                    Bound::Exact => return entry.score,
+                    Bound::Lower => alpha = alpha.max(entry.score),
+                    Bound::Upper => beta = beta.min(entry.score),
+                }
+
+                if alpha >= beta {
+                    return entry.score;
                 }
             }
         }
 
-        leaves
-    }
+        let mut moves = self.available_turns(board);
+        if moves.is_empty() {
+            return Self::loss_score(board, depth);
+        }
 
-    /// Expand all given nodes and return newly inserted layer of nodes
-    fn expand_layer(&mut self, tree: &mut Arena<BoardNode>, nodes: Vec<NodeId>) -> Vec<NodeId> {
+        Self::order_moves(&mut moves);
 
-        nodes
-            .into_iter().map(
-                |n| self.expand_node(tree, n)
-            ).flatten()
-            .collect()
-    }
+        // best move from the last time this position was searched goes first
+        if let Some((from, to)) = best_move {
+            if let Some(i) = moves.iter().position(|m| m.from == from && m.to == to) {
+                moves[..=i].rotate_right(1);
+            }
+        }
 
-    /// Insert all possible next boards as children of given board node
-    fn expand_node(&mut self, tree: &mut Arena<BoardNode>, node: NodeId) -> Vec<NodeId> {
-        let node_board: Option<&Node<BoardNode>> = tree.get(node);
-        
-        match node_board {
-            Some(node_ref) => {
+        let (alpha_start, beta_start) = (alpha, beta);
+        let mut best = match board.current_turn {
+            Black => isize::MIN,
+            White => isize::MAX,
+        };
+
+        for m in moves {
+            let score = self.alpha_beta(&m.apply(board), depth - 1, alpha, beta);
+
+            let improved = match board.current_turn {
+                Black => score > best,
+                White => score < best,
+            };
+            if improved {
+                best = score;
+                best_move = Some((m.from, m.to));
+            }
 
-                let board: &BoardNode = &*node_ref.get();
+            match board.current_turn {
+                Black => alpha = alpha.max(score),
+                White => beta = beta.min(score),
+            }
+            if alpha >= beta {
+                break;
+            }
+        }
 
-                // possible boards from given
-                let boards = self.get_move_boards(board);
+        if !self.aborted {
+            let bound = if best <= alpha_start {
+                Bound::Upper
+            } else if best >= beta_start {
+                Bound::Lower
+            } else {
+                Bound::Exact
+            };
 
-                // insert possible boards
-                let ids = self.insert_boards(tree, boards);
-                // append ids to root node
-                ids.iter().for_each( |id| node.append(*id, tree) );
-                
-                return ids;
-            },
-            None => {
-                panic!("No board retrieved from tree, node: {:?}", node);
-            },
+            self.table.insert(Entry { hash, depth, score: best, bound, best_move });
         }
+
+        best
     }
 
-    /// Insert given boards into tree
-    fn insert_boards(&mut self, tree: &mut Arena<BoardNode>, boards: Vec<BoardNode>) -> Vec<NodeId> {
-        
-        boards
-            .into_iter().map(
-                |b| tree.new_node(b)
-            ).collect()
-    }
-
-    /// Get all possible next boards from given board
-    fn get_move_boards(&self, board: &BoardNode) -> Vec<BoardNode> {
-
-        self.available_turns(&board.board)
-            .into_iter().map(
-                |m| {
-                    match m.mv_type {
-                        MoveType::Move => BoardNode::brd(
-                            board.board.apply_move(m.from, m.to)
-                        ),
-                        MoveType::Jump => BoardNode::brd(
-                            board.board.apply_jump(m.from, m.to)
-                        ),
-                    }
-                }
-            ).collect()
+    /// Score each of the next boards from the given by searching to the given depth, only those as good as the best are 
+    /// given exact scores. Moves are sorted into the order searched and the scores are returned in the same order
+    /// 
+    /// Each move after the first is searched with a window just below the best score so far, letting moves that can't
+    /// match it be cut off early while still finding every move that is equally good
+    fn score_moves(&mut self, board: &Board, moves: &mut [Move], depth: usize) -> Vec<BoardNode> {
+        Self::order_moves(moves);
+
+        let depth = depth.saturating_sub(1);
+        self.depth_limited = false;
+
+        let mut best: Option<isize> = None;
+        let mut scored = Vec::with_capacity(moves.len());
+
+        for m in moves.iter() {
+            let next = m.apply(board);
+
+            let score = match (best, board.current_turn) {
+                (None, _) => self.alpha_beta(&next, depth, isize::MIN, isize::MAX),
+                (Some(best), Black) => self.alpha_beta(&next, depth, best - 1, isize::MAX),
+                (Some(best), White) => self.alpha_beta(&next, depth, isize::MIN, best + 1),
+            };
+
+            best = match (best, board.current_turn) {
+                (None, _) => Some(score),
+                (Some(best), Black) => Some(best.max(score)),
+                (Some(best), White) => Some(best.min(score)),
+            };
+
+            scored.push(BoardNode::new(next, score));
+        }
+
+        scored
     }
 
     /// Get a new board based on the given using MiniMax to make decisions 
-    pub fn get_move(&mut self, brd: Board) -> Option<Board> {
-
-        #[allow(unused_assignments)]
-        let mut ret: Option<Board> = None;
+    /// 
+    /// [`None`] when there are no moves, fails if the board's squares are inconsistent, see [`Board::check`]
+    pub fn get_move(&mut self, brd: Board) -> Result<Option<Board>, DraughtError> {
+        self.last_node_count = 0;
+        self.last_depth = 0;
+        self.table.clear();
+        brd.check()?;
+
+        let mut moves = self.available_turns(&brd);
+        if moves.is_empty() {
+            return Ok(None);
+        }
 
-        let mut tree = Arena::new();
+        if let Some(book) = self.book_move(&brd) {
+            return Ok(Some(book));
+        }
 
-        // generate a tree to given depth for the given board
-        let root_node = self.gen_tree(&mut tree, brd);
+        if let Some(endgame) = self.tablebase_move(&brd) {
+            return Ok(Some(endgame));
+        }
 
-        self.last_node_count = tree.count() - 1;
+        if let Some(random) = self.random_move(&brd, &moves) {
+            return Ok(Some(random));
+        }
 
-        // get the leaf nodes to insert the board scores with
-        let lowest_nodes = self.get_leaf_nodes(&mut tree, root_node);
+        let scored = self.score_moves(&brd, &mut moves, self.search_depth);
+        self.last_depth = self.search_depth;
 
-        // insert the board scores for the leaf nodes
-        self.insert_board_scores(&mut tree, lowest_nodes);
+        Ok(Self::perfect_choice(&brd, scored, &mut self.rng))
+    }
 
-        // propagate the scores up the tree, the root node has the best score
-        let tree = Computer::propagate_scores(tree, root_node);
+    /// Get a new board based on the given, searching deeper until the given time has passed
+    /// 
+    /// Each iteration searches a move further ahead with the moves sorted by the previous iteration's scores, the move
+    /// is chosen from the deepest iteration to finish in time. The first iteration is always finished. Fails like
+    /// [`Computer::get_move`]
+    pub fn search_for(&mut self, brd: Board, budget: Duration) -> Result<Option<Board>, DraughtError> {
+        self.last_node_count = 0;
+        self.last_depth = 0;
+        self.table.clear();
+        brd.check()?;
+
+        let mut moves = self.available_turns(&brd);
+        if moves.is_empty() {
+            return Ok(None);
+        }
 
-        // get root node to compare
-        let root_board_node = tree
-            .get(root_node) // get Node
-            .expect("No node returned for node id")
-            .get(); // get BoardNode from Node
+        if let Some(book) = self.book_move(&brd) {
+            return Ok(Some(book));
+        }
 
-        // node ids of available next moves
-        let possible_moves: Vec<NodeId> = root_node.children(&tree).collect();
+        if let Some(endgame) = self.tablebase_move(&brd) {
+            return Ok(Some(endgame));
+        }
 
-        if possible_moves.len() == 0 {
-            return None;
+        if let Some(random) = self.random_move(&brd, &moves) {
+            return Ok(Some(random));
         }
 
-        // DEBUG
-        #[cfg(feature = "debug_logs")]
-        {
-            log!("Current root score: {}", root_board_node.score);
-            let scores: Vec<isize> = possible_moves
-                .iter()
-                .map(|n| tree.get(*n).unwrap().get().score)
+        let deadline = Deadline::after(budget);
+
+        let mut scored = self.score_moves(&brd, &mut moves, 1);
+        self.last_depth = 1;
+
+        self.deadline = Some(deadline);
+        self.aborted = false;
+
+        for depth in 2..=MAX_SEARCH_DEPTH {
+            // every line already reaches the end of the game
+            if !self.depth_limited || deadline.passed() {
+                break;
+            }
+
+            // search the best moves from the last iteration first
+            let mut order: Vec<(Move, isize)> = moves.into_iter()
+                .zip(scored.iter().map(|n| n.score))
                 .collect();
-            log!("Next boards scores: {:?}", scores);
+            match brd.current_turn {
+                White => order.sort_by_key(|(_, score)| *score),
+                Black => order.sort_by_key(|(_, score)| std::cmp::Reverse(*score)),
+            }
+            moves = order.into_iter().map(|(m, _)| m).collect();
+
+            let iteration = self.score_moves(&brd, &mut moves, depth);
+            if self.aborted {
+                break;
+            }
+
+            scored = iteration;
+            self.last_depth = depth;
         }
 
-        let mut rng = rand::thread_rng();
+        self.deadline = None;
+        self.aborted = false;
+
+        Ok(Self::perfect_choice(&brd, scored, &mut self.rng))
+    }
+
+    /// Pick a move from the opening book, [`None`] if there's no book or the position isn't in it
+    fn book_move(&mut self, brd: &Board) -> Option<Board> {
+        self.book.as_ref()?
+            .choose(brd, &mut self.rng)
+            .map(|m| m.apply(brd))
+    }
+
+    /// Pick the best move from the tablebase, [`None`] if there's no tablebase or the position isn't in it
+    fn tablebase_move(&mut self, brd: &Board) -> Option<Board> {
+        self.tablebase.as_ref()?
+            .best_move(brd, &mut self.rng)
+            .map(|m| m.apply(brd))
+    }
+
+    /// Pick a random move with the chance of not making a perfect move, [`None`] if a perfect move should be made
+    fn random_move(&mut self, brd: &Board, moves: &[Move]) -> Option<Board> {
         // random number to compare against threshold
-        let perfect_num: f64 = rng.gen();
+        let perfect_num: f64 = self.rng.gen();
 
-        // make perfect move
         if perfect_num < self.perfect_chance {
             #[cfg(feature = "debug_logs")]
             log!("Making perfect move");
 
-            // get boards of equal score that are perfect for the given player
-            let possible_perfect_moves: Vec<&BoardNode> = possible_moves
-                .iter()
-                .map(
-                    // get immutable references to BoardNodes for possible moves
-                    |n| tree
-                        .get(*n) // get Node using NodeID
-                        .expect("Unable to get perfect move data from tree node")
-                        .get() // get *BoardNode from Node
-                )
-                .filter(
-                    // filter for only scores of root node which are perfect moves
-                    |b| b.score == root_board_node.score
-                )
-                .collect();
+            return None;
+        }
 
-            // weird error, no child nodes have same score as root node
-            // this is odd because the root nodes score is either the max or min of it's children
-            if possible_perfect_moves.len() == 0 {
-                // log_error!("No next moves matched the score of the root node, picking randomly instead");
-                
-                ret = Some(Computer::random_choice(&tree, possible_moves, &mut rng));
-            }
-            // only one possible move, use that
-            else if possible_perfect_moves.len() == 1 {
-                ret = Some(possible_perfect_moves[0].board.clone());
-            }
-            // more than one possible perfect move to make, choose one randomly
-            else {
-                ret = Some(
-                    possible_perfect_moves
-                        .choose(&mut rng) // random choice
-                        .unwrap() // unwrap Option
-                        .board
-                        .clone()
-                );
-            }
-        } 
-        // get random move
-        else {
-            #[cfg(feature = "debug_logs")]
-            log!("Making random move");
+        #[cfg(feature = "debug_logs")]
+        log!("Making random move");
 
-            ret = Some(Computer::random_choice(&tree, possible_moves, &mut rng));
+        moves.choose(&mut self.rng).map(|m| m.apply(brd))
+    }
+
+    /// Choose randomly between the searched boards with the best score for the player to move
+    fn perfect_choice(brd: &Board, scored: Vec<BoardNode>, rng: &mut StdRng) -> Option<Board> {
+        let best_score = match brd.current_turn {
+            White => scored.iter().map(|n| n.score).min(),
+            Black => scored.iter().map(|n| n.score).max(),
+        }?;
+
+        // DEBUG
+        #[cfg(feature = "debug_logs")]
+        {
+            log!("Best score: {}", best_score);
+            let scores: Vec<isize> = scored.iter().map(|n| n.score).collect();
+            log!("Next boards scores: {:?}", scores);
         }
 
-        ret
-    }
+        // choose randomly between boards of equal score that are perfect for the given player
+        let possible_perfect_moves: Vec<BoardNode> = scored
+            .into_iter()
+            .filter(|n| n.score == best_score)
+            .collect();
 
-    /// Get a random board from possible node IDs and associated tree
-    fn random_choice(tree: &Arena<BoardNode>, possible_moves: Vec<NodeId>, rng: &mut ThreadRng) -> Board {
-        let chosen_move = possible_moves.choose(rng).unwrap();
-        tree
-            .get(*chosen_move)
-            .expect("Unable to get random move data from tree node")
-            .get()
-            .board
-            .clone()
+        possible_perfect_moves
+            .choose(rng)
+            .map(|n| n.board.clone())
     }
-}
\ No newline at end of file
+}
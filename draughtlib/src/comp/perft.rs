@@ -0,0 +1,42 @@
+//! Perft move generation counts for checking that the legal moves are correct
+//!
+//! The number of move sequences of a given length from a position can be compared against published counts, a
+//! regression in [`Board::legal_moves`] shows up as a different count
+
+use crate::board::Board;
+
+use super::Move;
+
+/// Count the move sequences of the given number of turns from a board, multi-jumps count as one turn
+pub fn perft(board: &Board, depth: usize) -> usize {
+    if depth == 0 {
+        return 1;
+    }
+
+    let moves = board.legal_moves();
+
+    // leaves don't need to be made, only counted
+    if depth == 1 {
+        return moves.len();
+    }
+
+    moves
+        .iter()
+        .map(|mv| perft(&mv.apply(board), depth - 1))
+        .sum()
+}
+
+/// Break the [`perft`] count down by each move available from the board
+pub fn divide(board: &Board, depth: usize) -> Vec<(Move, usize)> {
+    if depth == 0 {
+        return Vec::new();
+    }
+
+    board.legal_moves()
+        .into_iter()
+        .map(|mv| {
+            let nodes = perft(&mv.apply(board), depth - 1);
+            (mv, nodes)
+        })
+        .collect()
+}
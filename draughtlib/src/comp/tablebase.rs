@@ -0,0 +1,594 @@
+//! Endgame tablebases for perfect play once few pieces are left
+//!
+//! Every position with up to a given number of pieces is solved by retrograde analysis, working back from the positions
+//! where the player to move has lost. Positions are split into slices by the number of men and kings each team has, a
+//! capture or a man being crowned always leads to a slice that's already solved so each slice only has to be
+//! repeatedly swept until none of its positions change.
+//!
+//! Each position is stored in 2 bytes as a win, loss or draw for the player to move with the number of turns until the
+//! game is won. Positions are indexed by ranking the squares of each type of piece as a combination, see
+//! [`Tablebase::to_bytes`] for the file layout
+
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::fmt::{Display};
+
+use rand::Rng;
+use rand::seq::SliceRandom;
+
+use crate::board::Board;
+use crate::board::bitboard::{BitBoard, BitBoardError, bits};
+use crate::board::enums::Team;
+use crate::board::rules::{Rules, CaptureRule, Promotion};
+
+use super::Move;
+
+/// Start of every tablebase file
+const MAGIC: &[u8; 4] = b"DRTB";
+/// Version of the file layout
+const VERSION: u8 = 1;
+
+/// Most pieces a tablebase can be generated for, slices get too large to hold in memory past this
+pub const MAX_TABLEBASE_PIECES: usize = 6;
+
+/// Stored value for a draw, the value for positions that can't happen is the same
+const DRAW: u16 = 0;
+/// Value for a position that hasn't been solved yet during generation
+const UNRESOLVED: u16 = u16::MAX;
+
+/// Outcome for the player to move with best play from both sides
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Win,
+    Loss,
+    Draw,
+}
+
+/// Result of looking a position up in a [`Tablebase`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Probe {
+    /// Outcome for the player to move
+    pub outcome: Outcome,
+    /// Number of turns until the game is won with best play, 0 for a draw
+    pub distance: usize,
+}
+
+impl Probe {
+    fn decode(value: u16) -> Probe {
+        match value {
+            DRAW => Probe { outcome: Outcome::Draw, distance: 0 },
+            _ if value & 1 == 1 => Probe { outcome: Outcome::Win, distance: (value as usize - 1) / 2 },
+            _ => Probe { outcome: Outcome::Loss, distance: (value as usize - 2) / 2 },
+        }
+    }
+
+    fn encode(&self) -> u16 {
+        match self.outcome {
+            Outcome::Draw => DRAW,
+            Outcome::Win => (1 + 2 * self.distance) as u16,
+            Outcome::Loss => (2 + 2 * self.distance) as u16,
+        }
+    }
+
+    fn win(distance: usize) -> Probe {
+        Probe { outcome: Outcome::Win, distance }
+    }
+
+    fn loss(distance: usize) -> Probe {
+        Probe { outcome: Outcome::Loss, distance }
+    }
+}
+
+/// Problems generating or reading a tablebase
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TablebaseError {
+    /// Rules' board can't be held in a bitboard
+    UnsupportedRules(BitBoardError),
+    /// More pieces than [`MAX_TABLEBASE_PIECES`]
+    TooManyPieces(usize),
+    /// File doesn't start with the tablebase header
+    InvalidHeader,
+    /// File ends part-way through
+    Truncated,
+}
+
+impl From<BitBoardError> for TablebaseError {
+    fn from(e: BitBoardError) -> TablebaseError {
+        TablebaseError::UnsupportedRules(e)
+    }
+}
+
+impl Display for TablebaseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TablebaseError::UnsupportedRules(e) => write!(f, "Unsupported rules: {}", e),
+            TablebaseError::TooManyPieces(pieces) => write!(f, "Can't generate a tablebase for {} pieces, at most {}", pieces, MAX_TABLEBASE_PIECES),
+            TablebaseError::InvalidHeader => write!(f, "Not a tablebase file"),
+            TablebaseError::Truncated => write!(f, "Tablebase file is truncated"),
+        }
+    }
+}
+
+impl std::error::Error for TablebaseError {}
+
+/// Number of each type of piece, positions with the same material are stored together
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Material {
+    white_men: u8,
+    white_kings: u8,
+    black_men: u8,
+    black_kings: u8,
+}
+
+impl Material {
+    fn of(bitboard: &BitBoard) -> Material {
+        let kings = bitboard.kings();
+        let (white, black) = (bitboard.pieces(Team::White), bitboard.pieces(Team::Black));
+
+        Material {
+            white_men: (white & !kings).count_ones() as u8,
+            white_kings: (white & kings).count_ones() as u8,
+            black_men: (black & !kings).count_ones() as u8,
+            black_kings: (black & kings).count_ones() as u8,
+        }
+    }
+
+    fn groups(&self) -> [u8; 4] {
+        [self.white_men, self.white_kings, self.black_men, self.black_kings]
+    }
+
+    fn pieces(&self) -> usize {
+        self.groups().iter().map(|n| *n as usize).sum()
+    }
+
+    fn men(&self) -> usize {
+        (self.white_men + self.black_men) as usize
+    }
+}
+
+/// Position being solved with what's known about the positions it leads to
+struct Unsolved {
+    index: usize,
+    /// Positions it leads to in the same slice
+    next: Range<usize>,
+    /// Quickest win through a position in another slice
+    fastest_win: Option<usize>,
+    /// Slowest loss if every position in another slice is lost, [`None`] if any aren't
+    slowest_loss: Option<usize>,
+}
+
+impl Unsolved {
+    /// Take a position that can be moved to into account
+    fn follow(&mut self, next: Probe) {
+        match next.outcome {
+            Outcome::Loss => {
+                self.fastest_win = Some(self.fastest_win.map_or(next.distance + 1, |win| win.min(next.distance + 1)));
+                self.slowest_loss = None;
+            },
+            Outcome::Win => self.slowest_loss = self.slowest_loss.map(|loss| loss.max(next.distance + 1)),
+            Outcome::Draw => self.slowest_loss = None,
+        }
+    }
+
+    /// Outcome if the position is won or lost in `distance` turns, using the positions solved in fewer turns
+    fn solve(&self, next: &[usize], values: &[u16], distance: usize) -> Option<Probe> {
+        let mut result = Unsolved { next: 0..0, ..*self };
+
+        for index in &next[self.next.clone()] {
+            match values[*index] {
+                UNRESOLVED => result.slowest_loss = None,
+                value => result.follow(Probe::decode(value)),
+            }
+        }
+
+        match (result.fastest_win, result.slowest_loss) {
+            (Some(win), _) if win <= distance => Some(Probe::win(win)),
+            (_, Some(loss)) if loss <= distance => Some(Probe::loss(loss)),
+            _ => None,
+        }
+    }
+}
+
+/// Solved positions for every material balance up to a number of pieces for one set of rules
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Tablebase {
+    rules: Rules,
+    max_pieces: usize,
+    /// Empty bitboard for the rules, used to make positions
+    empty: BitBoard,
+    /// Bit index of each playable square in order
+    squares: Vec<u32>,
+    /// Index into `squares` for each bit
+    square_of: Vec<u8>,
+    /// Binomial coefficients, `binomial[n][k]` is n choose k
+    binomial: Vec<Vec<usize>>,
+    slices: BTreeMap<Material, Vec<u16>>,
+}
+
+impl Tablebase {
+    /// Create a tablebase without any solved positions
+    fn empty(rules: Rules, max_pieces: usize) -> Result<Tablebase, TablebaseError> {
+        if max_pieces > MAX_TABLEBASE_PIECES {
+            return Err(TablebaseError::TooManyPieces(max_pieces));
+        }
+
+        let empty = BitBoard::new(rules, Team::White)?;
+        let squares: Vec<u32> = bits(empty.playable()).collect();
+
+        let mut square_of = vec![0; 64];
+        for (i, bit) in squares.iter().enumerate() {
+            square_of[*bit as usize] = i as u8;
+        }
+
+        let mut binomial = vec![vec![0; max_pieces + 1]; squares.len() + 1];
+        for n in 0..=squares.len() {
+            binomial[n][0] = 1;
+            for k in 1..=max_pieces.min(n) {
+                binomial[n][k] = binomial[n - 1][k - 1] + binomial[n - 1][k];
+            }
+        }
+
+        Ok(Tablebase {
+            rules,
+            max_pieces,
+            empty,
+            squares,
+            square_of,
+            binomial,
+            slices: BTreeMap::new(),
+        })
+    }
+
+    /// Solve every position with up to `max_pieces` pieces where both teams have at least one piece
+    pub fn generate(rules: Rules, max_pieces: usize) -> Result<Tablebase, TablebaseError> {
+        let mut tablebase = Tablebase::empty(rules, max_pieces)?;
+
+        let mut materials = Vec::new();
+        for white_men in 0..=max_pieces as u8 {
+            for white_kings in 0..=max_pieces as u8 - white_men {
+                for black_men in 0..=max_pieces as u8 - white_men - white_kings {
+                    for black_kings in 0..=max_pieces as u8 - white_men - white_kings - black_men {
+                        if white_men + white_kings > 0 && black_men + black_kings > 0 {
+                            materials.push(Material { white_men, white_kings, black_men, black_kings });
+                        }
+                    }
+                }
+            }
+        }
+
+        // captures lead to fewer pieces and crowning leads to fewer men, so those slices are solved first
+        materials.sort_by_key(|m| (m.pieces(), m.men()));
+
+        for material in materials {
+            let values = tablebase.solve(material);
+            tablebase.slices.insert(material, values);
+        }
+
+        Ok(tablebase)
+    }
+
+    /// Rules that the positions were solved for
+    pub fn rules(&self) -> Rules {
+        self.rules
+    }
+
+    /// Most pieces in a solved position
+    pub fn max_pieces(&self) -> usize {
+        self.max_pieces
+    }
+
+    /// Number of positions stored, including those that can't happen
+    pub fn len(&self) -> usize {
+        self.slices.values().map(Vec::len).sum()
+    }
+
+    /// Whether no positions are stored
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of positions for a material balance, for each player to move
+    fn slice_len(&self, material: Material) -> usize {
+        let squares = self.squares.len();
+        2 * material.groups().iter().map(|n| self.binomial[squares][*n as usize]).product::<usize>()
+    }
+
+    /// Combinatorial rank of a set of squares
+    fn rank(&self, mask: u64) -> usize {
+        bits(mask)
+            .enumerate()
+            .map(|(i, bit)| self.binomial[self.square_of[bit as usize] as usize][i + 1])
+            .sum()
+    }
+
+    /// Set of `count` squares with the given combinatorial rank
+    fn unrank(&self, mut rank: usize, count: usize) -> u64 {
+        let mut mask = 0;
+        let mut below = self.squares.len();
+
+        for k in (1..=count).rev() {
+            // largest square with k - 1 others below it that fits in what's left of the rank
+            below -= 1;
+            while self.binomial[below][k] > rank {
+                below -= 1;
+            }
+
+            rank -= self.binomial[below][k];
+            mask |= 1 << self.squares[below];
+        }
+
+        mask
+    }
+
+    /// Index of a position within its material's slice
+    fn index(&self, material: Material, bitboard: &BitBoard) -> usize {
+        let kings = bitboard.kings();
+        let (white, black) = (bitboard.pieces(Team::White), bitboard.pieces(Team::Black));
+        let squares = self.squares.len();
+
+        [(white & !kings, material.white_men), (white & kings, material.white_kings), (black & !kings, material.black_men), (black & kings, material.black_kings)]
+            .iter()
+            .fold(bitboard.current_turn as usize, |index, (mask, count)| {
+                index * self.binomial[squares][*count as usize] + self.rank(*mask)
+            })
+    }
+
+    /// Position for an index within a material's slice, [`None`] for positions that can't happen
+    fn position(&self, material: Material, index: usize) -> Option<BitBoard> {
+        let squares = self.squares.len();
+        let mut rest = index;
+        let mut masks = [0; 4];
+
+        for (group, count) in material.groups().iter().enumerate().rev() {
+            let combinations = self.binomial[squares][*count as usize];
+            masks[group] = self.unrank(rest % combinations, *count as usize);
+            rest /= combinations;
+        }
+
+        let [white_men, white_kings, black_men, black_kings] = masks;
+        let current_turn = if rest == 0 { Team::Black } else { Team::White };
+
+        // pieces on the same square
+        if (white_men | white_kings | black_men | black_kings).count_ones() as usize != material.pieces() {
+            return None;
+        }
+
+        // men on the row they'd have been crowned on
+        if white_men & self.empty.king_row(Team::White) != 0 || black_men & self.empty.king_row(Team::Black) != 0 {
+            return None;
+        }
+
+        Some(self.empty.with_pieces(white_men | white_kings, black_men | black_kings, white_kings | black_kings, current_turn))
+    }
+
+    /// Solve every position for a material balance, every slice that it can lead to has to be solved already
+    fn solve(&self, material: Material) -> Vec<u16> {
+        let mut values = vec![DRAW; self.slice_len(material)];
+        let mut pending = Vec::new();
+        // indices of the positions that unsolved positions lead to in the same slice, `Unsolved::next` is a range of it
+        let mut next = Vec::new();
+
+        for (index, value) in values.iter_mut().enumerate() {
+            let Some(bitboard) = self.position(material, index) else {
+                continue;
+            };
+
+            let moves = bitboard.moves();
+            if moves.is_empty() {
+                *value = Probe::loss(0).encode();
+                continue;
+            }
+
+            // results through other slices are already known
+            let start = next.len();
+            let mut unsolved = Unsolved { index, next: start..start, fastest_win: None, slowest_loss: Some(0) };
+
+            for mv in moves {
+                let after = bitboard.apply(&mv);
+
+                // taking the last piece wins
+                let probe = if after.pieces(after.current_turn) == 0 {
+                    Probe::loss(0)
+                } else {
+                    let after_material = Material::of(&after);
+                    if after_material == material {
+                        next.push(self.index(material, &after));
+                        continue;
+                    }
+
+                    Probe::decode(self.slices[&after_material][self.index(after_material, &after)])
+                };
+
+                unsolved.follow(probe);
+            }
+
+            unsolved.next = start..next.len();
+            *value = UNRESOLVED;
+            pending.push(unsolved);
+        }
+
+        // positions in other slices can be won or lost in up to this many turns
+        let longest = self.slices
+            .values()
+            .flatten()
+            .filter(|v| **v != DRAW)
+            .map(|v| Probe::decode(*v).distance)
+            .max()
+            .unwrap_or(0);
+
+        // each sweep finds the positions won or lost in `distance` turns, results are only stored once a sweep is done so
+        // that each position is found on the sweep for its own distance
+        let mut distance = 1;
+        loop {
+            let mut solved = Vec::new();
+
+            pending.retain(|unsolved| {
+                match unsolved.solve(&next, &values, distance) {
+                    Some(probe) => {
+                        solved.push((unsolved.index, probe.encode()));
+                        false
+                    },
+                    None => true,
+                }
+            });
+
+            let changed = !solved.is_empty();
+            for (index, value) in solved {
+                values[index] = value;
+            }
+
+            if pending.is_empty() || (!changed && distance > longest) {
+                break;
+            }
+            distance += 1;
+        }
+
+        // nobody can force a win from what's left
+        for unsolved in pending {
+            values[unsolved.index] = DRAW;
+        }
+
+        values
+    }
+
+    /// Look up a bitboard position, [`None`] when it has too many pieces or is for other rules
+    pub fn probe_bitboard(&self, bitboard: &BitBoard) -> Option<Probe> {
+        if !self.covers_rules(&bitboard.rules()) {
+            return None;
+        }
+
+        if bitboard.pieces(bitboard.current_turn) == 0 {
+            return Some(Probe::loss(0));
+        }
+
+        let material = Material::of(bitboard);
+        let values = self.slices.get(&material)?;
+
+        Some(Probe::decode(values[self.index(material, bitboard)]))
+    }
+
+    /// Look up a board's position, [`None`] when it has too many pieces, is part-way through a multi-jump or is for other
+    /// rules
+    pub fn probe(&self, board: &Board) -> Option<Probe> {
+        if board.num_pieces() > self.max_pieces {
+            return None;
+        }
+
+        self.probe_bitboard(&BitBoard::from_board(board).ok()?)
+    }
+
+    /// Whether positions for the given rules are in the tablebase, the number of starting rows doesn't matter
+    fn covers_rules(&self, rules: &Rules) -> bool {
+        rules.width == self.rules.width && rules.height == self.rules.height && rules.same_play(&self.rules)
+    }
+
+    /// Get a move with the best outcome for the player to move, [`None`] when the board isn't in the tablebase
+    ///
+    /// Wins are taken as quickly as possible and losses put off as long as possible, equally good moves are picked at random
+    pub fn best_move<R: Rng>(&self, board: &Board, rng: &mut R) -> Option<Move> {
+        self.probe(board)?;
+
+        let scored: Vec<(Move, isize)> = board.legal_moves()
+            .into_iter()
+            .filter_map(|mv| {
+                // outcome for the other player after the move
+                let probe = self.probe(&mv.apply(board))?;
+                let score = match probe.outcome {
+                    Outcome::Loss => isize::MAX - probe.distance as isize,
+                    Outcome::Draw => 0,
+                    Outcome::Win => isize::MIN + probe.distance as isize,
+                };
+
+                Some((mv, score))
+            })
+            .collect();
+
+        let best = scored.iter().map(|(_, score)| *score).max()?;
+        let best_moves: Vec<Move> = scored.into_iter()
+            .filter(|(_, score)| *score == best)
+            .map(|(mv, _)| mv)
+            .collect();
+
+        best_moves.choose(rng).cloned()
+    }
+
+    /// Write the tablebase as bytes
+    ///
+    /// The header is `DRTB`, a version byte, the rules as width, height, piece rows, flying kings, men capturing backwards, capture
+    /// rule and promotion bytes, then the number of pieces. Each slice follows as its number of white men, white kings,
+    /// black men and black kings then every position's value as a little-endian `u16`, Black to move first
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(16 + 2 * self.len() + 4 * self.slices.len());
+
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        bytes.extend_from_slice(&[
+            self.rules.width as u8,
+            self.rules.height as u8,
+            self.rules.piece_rows as u8,
+            self.rules.flying_kings as u8,
+            self.rules.men_capture_backwards as u8,
+            self.rules.capture_rule as u8,
+            self.rules.promotion as u8,
+            self.max_pieces as u8,
+        ]);
+
+        for (material, values) in &self.slices {
+            bytes.extend_from_slice(&material.groups());
+
+            for value in values {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+
+        bytes
+    }
+
+    /// Read a tablebase written by [`Tablebase::to_bytes`]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Tablebase, TablebaseError> {
+        let header = bytes.get(..13).ok_or(TablebaseError::Truncated)?;
+        if &header[..4] != MAGIC || header[4] != VERSION {
+            return Err(TablebaseError::InvalidHeader);
+        }
+
+        let capture_rule = match header[10] {
+            0 => CaptureRule::Free,
+            1 => CaptureRule::Majority,
+            _ => return Err(TablebaseError::InvalidHeader),
+        };
+        let promotion = match header[11] {
+            0 => Promotion::EndsTurn,
+            1 => Promotion::ContinuesAsKing,
+            2 => Promotion::AtEndOfMove,
+            _ => return Err(TablebaseError::InvalidHeader),
+        };
+
+        let (width, height, piece_rows) = (header[5] as usize, header[6] as usize, header[7] as usize);
+        let rules = Rules::new(width, height, piece_rows, header[8] != 0, header[9] != 0, capture_rule, promotion);
+
+        let mut tablebase = Tablebase::empty(rules, header[12] as usize)?;
+        let mut rest = &bytes[13..];
+
+        while !rest.is_empty() {
+            let groups = rest.get(..4).ok_or(TablebaseError::Truncated)?;
+            let material = Material {
+                white_men: groups[0],
+                white_kings: groups[1],
+                black_men: groups[2],
+                black_kings: groups[3],
+            };
+
+            if material.pieces() > tablebase.max_pieces {
+                return Err(TablebaseError::InvalidHeader);
+            }
+
+            let len = tablebase.slice_len(material);
+            let data = rest.get(4..4 + 2 * len).ok_or(TablebaseError::Truncated)?;
+
+            tablebase.slices.insert(material, data.chunks_exact(2).map(|pair| u16::from_le_bytes([pair[0], pair[1]])).collect());
+            rest = &rest[4 + 2 * len..];
+        }
+
+        Ok(tablebase)
+    }
+}
@@ -5,6 +5,14 @@ use wasm_bindgen_test::*;
 
 use crate::board::Square;
 use crate::board::enums::Strength::*;
+use crate::board::rules::{Rules, Variant};
+use crate::board::tests::variant_start;
+use eval::{Weights, WeightedEvaluator};
+use perft::{perft, divide};
+use book::{OpeningBook, BookError};
+use tablebase::{Tablebase, Probe, Outcome, TablebaseError};
+use crate::board::bitboard::{BitBoard, bits};
+use std::sync::OnceLock;
 // use crate::log;
 
 // use Team::*;
@@ -33,7 +41,7 @@ fn available_moves() {
     // log!("{}", brd);
 
     // can move left and right from central square
-    brd.set_cell(brd.cell_index(0, 1), Square::pc(White, Man));
+    brd.set_cell(brd.cell_index(0, 1), Square::pc(White, Man)).unwrap();
 
     // log!("{}", brd);
 
@@ -58,8 +66,8 @@ fn available_moves_jumps() {
 
     // log!("{}", brd);
 
-    brd.set_cell(brd.cell_index(0, 1), Square::pc(White, Man));
-    brd.set_cell(brd.cell_index(1, 2), Square::pc(Black, Man));
+    brd.set_cell(brd.cell_index(0, 1), Square::pc(White, Man)).unwrap();
+    brd.set_cell(brd.cell_index(1, 2), Square::pc(Black, Man)).unwrap();
 
     // log!("{}", brd);
 
@@ -67,9 +75,9 @@ fn available_moves_jumps() {
 
     // log!("{:?}", moves);
 
-    assert_eq!(moves.len(), 2);
-    assert!(moves[0].mv_type == MoveType::Move);
-    assert!(moves[1].mv_type == MoveType::Jump);
+    // the standard move is not available as the jump must be taken
+    assert_eq!(moves.len(), 1);
+    assert!(moves[0].mv_type == MoveType::Jump);
 }
 
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
@@ -91,165 +99,176 @@ fn available_moves_std_brd() {
 
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
 #[cfg_attr(not(target_arch = "wasm32"), test)]
-fn expand_node() {
+fn search_node_count() {
     let brd = Board::init_game(Board::new(8, 8, White), 3);
-    let mut comp = Computer::new(3, White, 0.5);
-
-    // log!("{}", brd);
-
-    let mut tree = Arena::new(); 
-    let id = tree.new_node(BoardNode::brd(brd));
 
-    let moves = comp.expand_node(&mut tree, id);
+    let mut comp = Computer::new(1, White, 0.5);
+    comp.alpha_beta(&brd, 1, isize::MIN, isize::MAX);
+    // root and its 7 children
+    assert_eq!(comp.last_node_count, 8);
+
+    // pruning searches less than the 1 + 7 + 49 boards of the full tree
+    let mut comp = Computer::new(2, White, 0.5);
+    comp.alpha_beta(&brd, 2, isize::MIN, isize::MAX);
+    assert!(comp.last_node_count < 57);
+}
 
-    assert_eq!(moves.len(), 7);
-    assert_eq!(tree.count(), 8);
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn search_scores_all_take() {
+    // . _ . _ . 
+    // W . W . W 
+    // . B . B . 
+    // _ . _ . _ 
     
-    let children: Vec<NodeId> = id.children(&mut tree).collect();
-    assert_eq!(children.len(), 7);
+    // 4 available moves, all are white taking black and being crowned
+
+    let mut brd = Board::new(5, 4, White);
+    brd.set_cell(brd.cell_idx(BrdIdx::from(1, 2)), Square::pc(White, Man)).unwrap();
+    brd.set_cell(brd.cell_idx(BrdIdx::from(1, 0)), Square::pc(White, Man)).unwrap();
+    brd.set_cell(brd.cell_idx(BrdIdx::from(1, 4)), Square::pc(White, Man)).unwrap();
+    brd.set_cell(brd.cell_idx(BrdIdx::from(2, 1)), Square::pc(Black, Man)).unwrap();
+    brd.set_cell(brd.cell_idx(BrdIdx::from(2, 3)), Square::pc(Black, Man)).unwrap();
+    let mut comp = Computer::new(1, White, 1.);
+
+    let mut moves = comp.available_turns(&brd);
+    let scores: Vec<isize> = comp.score_moves(&brd, &mut moves, 1)
+        .into_iter()
+        .map(|n| n.score)
+        .collect();
+
+    assert_eq!(scores, vec!(-3, -3, -3, -3));
 }
 
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
 #[cfg_attr(not(target_arch = "wasm32"), test)]
-fn expand_layer() {
-    let brd = Board::init_game(Board::new(8, 8, White), 3);
-    let mut comp = Computer::new(3, White, 0.5);
+fn search_scores_one_take() {
+    // . _ . _ . 
+    // W . _ . W 
+    // . B . _ . 
+    // _ . _ . _ 
+    
+    // 1 available move, the other white piece can't move while a jump is available
 
-    // log!("{}", brd);
+    let mut brd = Board::new(5, 4, White);
+    brd.set_cell(brd.cell_idx(BrdIdx::from(1, 0)), Square::pc(White, Man)).unwrap();
+    brd.set_cell(brd.cell_idx(BrdIdx::from(1, 4)), Square::pc(White, Man)).unwrap();
+    brd.set_cell(brd.cell_idx(BrdIdx::from(2, 1)), Square::pc(Black, Man)).unwrap();
+    let mut comp = Computer::new(1, White, 1.);
+
+    let mut moves = comp.available_turns(&brd);
+    let scores: Vec<isize> = comp.score_moves(&brd, &mut moves, 1)
+        .into_iter()
+        .map(|n| n.score)
+        .collect();
+
+    assert_eq!(scores, vec!(-3));
+}
 
-    let mut tree = Arena::new(); 
-    let id = tree.new_node(BoardNode::brd(brd));
+/// Plain MiniMax over every board for checking the pruned search against
+fn minimax(comp: &Computer, board: &Board, depth: usize) -> isize {
+    if depth == 0 {
+        return board.score();
+    }
 
-    let moves = comp.expand_layer(&mut tree, vec!(id));
-    let moves = comp.expand_layer(&mut tree, moves);
+    let scores = comp.available_turns(board)
+        .into_iter()
+        .map(|m| minimax(comp, &m.apply(board), depth - 1));
 
-    assert_eq!(moves.len(), 49);
-    assert_eq!(tree.count(), 49 + 7 + 1);
+    match board.current_turn {
+        White => scores.min(),
+        Black => scores.max(),
+    }.unwrap_or_else(|| Computer::<Material>::loss_score(board, depth))
 }
 
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
 #[cfg_attr(not(target_arch = "wasm32"), test)]
-fn leaf_nodes() {
-    let brd = Board::init_game(Board::new(8, 8, White), 3);
-    let mut comp = Computer::new(3, White, 0.5);
-
-    let mut tree = Arena::new(); 
-    let id = tree.new_node(BoardNode::brd(brd));
-
-    let moves = comp.expand_node(&mut tree, id);    
-    let children: Vec<NodeId> = id.children(&mut tree).collect();
-    
-    let moves2 = comp.expand_node(&mut tree, children[0]);
+fn alpha_beta_matches_minimax() {
+    let mut brd = Board::init_game(Board::new(8, 8, Black), 3);
+    let mut comp = Computer::new(4, Black, 1.);
+
+    // check a few positions through a game
+    for _ in 0..6 {
+        for depth in 1..=4 {
+            assert_eq!(
+                comp.alpha_beta(&brd, depth, isize::MIN, isize::MAX), 
+                minimax(&comp, &brd, depth)
+            );
+        }
 
-    assert_eq!(comp.get_leaf_nodes(&mut tree, id).len(), moves.len() + moves2.len() - 1);
+        comp.team = brd.current_turn;
+        brd = comp.get_move(brd).unwrap().unwrap();
+    }
 }
 
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
 #[cfg_attr(not(target_arch = "wasm32"), test)]
-fn best_scores() {
-    let brd = Board::new(1, 1, White);
-    assert_eq!(Computer::best_score(&brd, vec!(-5, -1, 2, 3, 4)), -5);
+fn perfect_moves_match_minimax() {
+    let brd = Board::from_fen("W:W21,22,23,25,K30:B1,2,3,9,14,15", Rules::default()).unwrap();
+    let mut comp = Computer::new(4, White, 1.);
+
+    let mut moves = comp.available_turns(&brd);
+    let best = moves.iter()
+        .map(|m| minimax(&comp, &m.apply(&brd), 3))
+        .min()
+        .unwrap();
+    let perfect: Vec<Board> = moves.iter()
+        .filter(|m| minimax(&comp, &m.apply(&brd), 3) == best)
+        .map(|m| m.apply(&brd))
+        .collect();
+
+    // every move as good as the best gets the best score, the others are worse
+    for node in comp.score_moves(&brd, &mut moves, 4) {
+        assert_eq!(node.score == best, perfect.contains(&node.board));
+        assert!(node.score >= best);
+    }
 
-    let brd = Board::new(1, 1, Black);
-    assert_eq!(Computer::best_score(&brd, vec!(-1, 2, 3, 4)), 4);
+    for _ in 0..10 {
+        assert!(perfect.contains(&comp.get_move(brd.clone()).unwrap().unwrap()));
+    }
 }
 
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
 #[cfg_attr(not(target_arch = "wasm32"), test)]
-fn insert_scores_all_take() {
-    // . _ . _ . 
-    // W . W . W 
-    // . B . B . 
-    // _ . _ . _ 
-    
-    // 4 available moves, all are white taking black
-
-    let mut brd = Board::new(5, 4, White);
-    brd.set_cell(brd.cell_idx(BrdIdx::from(1, 2)), Square::pc(White, Man));
-    brd.set_cell(brd.cell_idx(BrdIdx::from(1, 0)), Square::pc(White, Man));
-    brd.set_cell(brd.cell_idx(BrdIdx::from(1, 4)), Square::pc(White, Man));
-    brd.set_cell(brd.cell_idx(BrdIdx::from(2, 1)), Square::pc(Black, Man));
-    brd.set_cell(brd.cell_idx(BrdIdx::from(2, 3)), Square::pc(Black, Man));
-    let mut comp = Computer::new(1, White, 0.5);
-
-    // log!("{}", brd);
-
-    let mut tree = Arena::new();
-    let root = tree.new_node(BoardNode::brd(brd));
+fn get_move_finds_win() {
+    // white king can take the last black piece, leaving black without moves with 2 plies of search left
+    let brd = Board::from_fen("W:WK18:B14", Rules::default()).unwrap();
+    let mut comp = Computer::new(3, White, 1.);
 
-    comp.expand_layer(&mut tree, vec!(root));
+    assert_eq!(comp.alpha_beta(&brd, 3, isize::MIN, isize::MAX), -WIN_SCORE - 2);
 
-    let lowest_nodes = comp.get_leaf_nodes(&mut tree, root);
-    // insert the board scores for the leaf nodes
-    comp.insert_board_scores(&mut tree, lowest_nodes);
-    
-    let children_scores: Vec<isize> = root // current node
-                    .children(&tree)
-                    .into_iter()
-                    .map(
-                        |n| tree
-                            .get(n) // get Node
-                            .expect("No node returned for node id") // unwrap, should always be fine
-                            .get() // get BoardNode from Node
-                            .score // get score from BoardNode
-                    )
-                    .collect(); // finalise
-
-    assert_eq!(children_scores, vec!(-3, -3, -3, -3));
+    // no moves left
+    let brd = Board::from_fen("B:W1:B", Rules::default()).unwrap();
+    assert_eq!(comp.get_move(brd).unwrap(), None);
 }
 
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
 #[cfg_attr(not(target_arch = "wasm32"), test)]
-fn insert_scores_one_take() {
-    // . _ . _ . 
-    // W . _ . W 
-    // . B . _ . 
-    // _ . _ . _ 
-    
-    // 4 available moves, all are white taking black
-
-    let mut brd = Board::new(5, 4, White);
-    // brd.set_cell(brd.cell_idx(BrdIdx::from(1, 2)), Square::pc(White, Man));
-    brd.set_cell(brd.cell_idx(BrdIdx::from(1, 0)), Square::pc(White, Man));
-    brd.set_cell(brd.cell_idx(BrdIdx::from(1, 4)), Square::pc(White, Man));
-    brd.set_cell(brd.cell_idx(BrdIdx::from(2, 1)), Square::pc(Black, Man));
-    // brd.set_cell(brd.cell_idx(BrdIdx::from(2, 3)), Square::pc(Black, Man));
-    let mut comp = Computer::new(1, White, 0.5);
-
-    // log!("{}", brd);
+fn move_ordering() {
+    // 23 can take 2 pieces, the others only 1
+    let brd = Board::from_fen("W:W23,24:B9,18,19", Rules::default()).unwrap();
+    let comp = Computer::new(1, White, 1.);
 
-    let mut tree = Arena::new();
-    let root = tree.new_node(BoardNode::brd(brd));
+    let mut moves = comp.available_turns(&brd);
+    Computer::<Material>::order_moves(&mut moves);
 
-    comp.expand_layer(&mut tree, vec!(root));
+    assert_eq!(moves.len(), 3);
+    assert_eq!(moves[0].hops.len(), 2);
+    assert!(moves[1..].iter().all(|m| m.hops.len() == 1));
 
-    let lowest_nodes = comp.get_leaf_nodes(&mut tree, root);
-    // insert the board scores for the leaf nodes
-    comp.insert_board_scores(&mut tree, lowest_nodes);
-    
-    let children_scores: Vec<isize> = root // current node
-                    .children(&tree)
-                    .into_iter()
-                    .map(
-                        |n| tree
-                            .get(n) // get Node
-                            .expect("No node returned for node id") // unwrap, should always be fine
-                            .get() // get BoardNode from Node
-                            .score // get score from BoardNode
-                    )
-                    .collect(); // finalise
-
-    // log!("{:?}", children_scores);
+    let brd = Board::from_fen("W:W21,K26,6:B1", Rules::default()).unwrap();
+    let mut moves = comp.available_turns(&brd);
+    Computer::<Material>::order_moves(&mut moves);
 
-    assert_eq!(children_scores, vec!(-3, -1));
+    // man on 6 being crowned comes first
+    assert_eq!(brd.square_number(moves[0].from), Some(6));
+    assert_eq!(moves[0].to.row, brd.king_row_idx());
 }
 
-#[cfg(feature = "time_ex")]
-#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
-#[cfg_attr(not(target_arch = "wasm32"), test)]
+#[cfg(all(feature = "time_ex", not(target_arch = "wasm32")))]
+#[test]
 fn tree_depth() {
 
-    let depth = 1;
     let iter = 5;
     
     for d in 1..8 {
@@ -260,31 +279,17 @@ fn tree_depth() {
     }
 }
 
-#[cfg(feature = "time_ex")]
-fn time_tree_gen(depth: usize) {
-    web_sys::console::time_with_label("tree_timer");
-
-    let mut comp = Computer::new(depth, White);
-
-    let mut tree = Arena::new();  
-    let brd = Board::init_game(Board::new(8, 8, White), 3);
-
-    comp.gen_tree(&mut tree, brd);
-
-    web_sys::console::time_end_with_label("tree_timer");
-}
-
-#[cfg(feature = "time_ex")]
+#[cfg(all(feature = "time_ex", not(target_arch = "wasm32")))]
 fn time_get_move(depth: usize) {
-    web_sys::console::time_with_label("tree_timer");
+    let start = std::time::Instant::now();
 
-    let mut comp = Computer::new(depth, White);
+    let mut comp = Computer::new(depth, White, 1.0);
  
     let brd = Board::init_game(Board::new(8, 8, White), 3);
 
-    comp.get_move(brd);
+    comp.get_move(brd).unwrap();
 
-    web_sys::console::time_end_with_label("tree_timer");
+    log!("tree_timer: {:?}", start.elapsed());
 }
 
 // #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
@@ -320,4 +325,599 @@ fn time_get_move(depth: usize) {
 
 //     let next = comp.get_move(next);
 //     // log!("{}", next);
-// }
\ No newline at end of file
+// }
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn available_moves_multi_jumps() {
+    // . _ . _ . _ . _ 
+    // _ . W . _ . _ . 
+    // . _ . B . _ . _ 
+    // _ . _ . _ . _ . 
+    // . _ . B . B . _ 
+    
+    let mut brd = Board::new(8, 8, White);
+    let comp = Computer::new(3, White, 0.5);
+
+    brd.set_cell(brd.cell_index(1, 2), Square::pc(White, Man)).unwrap();
+    brd.set_cell(brd.cell_index(2, 3), Square::pc(Black, Man)).unwrap();
+    brd.set_cell(brd.cell_index(4, 3), Square::pc(Black, Man)).unwrap();
+    brd.set_cell(brd.cell_index(4, 5), Square::pc(Black, Man)).unwrap();
+
+    let moves = comp.available_turns(&brd);
+
+    // two ways to carry on after the first hop
+    assert_eq!(moves.len(), 2);
+    assert!(moves.iter().all(|m| m.mv_type == MoveType::Jump && m.hops.len() == 2));
+    assert_eq!(moves[0].hops, vec![BrdIdx::from(3, 4), BrdIdx::from(5, 2)]);
+    assert_eq!(moves[1].hops, vec![BrdIdx::from(3, 4), BrdIdx::from(5, 6)]);
+
+    let next = moves[0].apply(&brd);
+    assert_eq!(next.current_turn, Black);
+    assert_eq!(next.num_player(Black), 1);
+
+    // part way through the jump, only the rest of the sequence is available
+    let mid = brd.apply_jump(BrdIdx::from(1, 2), BrdIdx::from(3, 4));
+    let moves = comp.available_turns(&mid);
+
+    assert_eq!(moves.len(), 2);
+    assert!(moves.iter().all(|m| m.from == BrdIdx::from(3, 4) && m.hops.len() == 1));
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn available_moves_majority_capture() {
+    let mut brd = Board::with_rules(Variant::International.rules(), White);
+    let comp = Computer::new(3, White, 0.5);
+
+    brd.set_cell(brd.cell_index(1, 2), Square::pc(White, Man)).unwrap();
+    brd.set_cell(brd.cell_index(2, 3), Square::pc(Black, Man)).unwrap();
+    brd.set_cell(brd.cell_index(4, 5), Square::pc(Black, Man)).unwrap();
+    brd.set_cell(brd.cell_index(7, 8), Square::pc(White, Man)).unwrap();
+    brd.set_cell(brd.cell_index(8, 7), Square::pc(Black, Man)).unwrap();
+
+    let moves = comp.available_turns(&brd);
+
+    // only the sequence taking 2 pieces
+    assert_eq!(moves.len(), 1);
+    assert_eq!(moves[0].from, BrdIdx::from(1, 2));
+    assert_eq!(moves[0].hops.len(), 2);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn available_moves_flying_king() {
+    let mut brd = Board::with_rules(Variant::Russian.rules(), White);
+    let comp = Computer::new(3, White, 0.5);
+
+    brd.set_cell(brd.cell_index(3, 4), Square::pc(White, King)).unwrap();
+
+    // every square along each diagonal
+    let moves = comp.available_turns(&brd);
+    assert_eq!(moves.len(), 13);
+    assert!(moves.iter().all(|m| m.mv_type == MoveType::Move));
+
+    brd.set_cell(brd.cell_index(5, 2), Square::pc(Black, Man)).unwrap();
+
+    // long capture with a choice of landing square
+    let moves = comp.available_turns(&brd);
+    assert_eq!(moves.len(), 2);
+    assert_eq!(moves[0].hops, vec![BrdIdx::from(6, 1)]);
+    assert_eq!(moves[1].hops, vec![BrdIdx::from(7, 0)]);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn search_for_time() {
+    let brd = Board::init_game(Board::new(8, 8, Black), 3);
+    let mut comp = Computer::new(1, Black, 1.);
+
+    let next = comp.search_for(brd.clone(), Duration::from_millis(50)).unwrap();
+
+    assert!(next.is_some());
+    assert!(comp.last_depth > 1);
+    assert!(comp.last_node_count > 0);
+
+    // always finishes the first iteration
+    comp.search_for(brd, Duration::ZERO).unwrap().unwrap();
+    assert_eq!(comp.last_depth, 1);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn search_for_stops_at_game_end() {
+    // white king takes the last black piece, no need to look further
+    let brd = Board::from_fen("W:WK18:B14", Rules::default()).unwrap();
+    let mut comp = Computer::new(1, White, 1.);
+
+    let next = comp.search_for(brd, Duration::from_secs(10)).unwrap().unwrap();
+
+    assert_eq!(next.num_player(Black), 0);
+    assert_eq!(comp.last_depth, 2);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn search_for_matches_depth_search() {
+    let brd = Board::from_fen("W:W21,22,23,25,K30:B1,2,3,9,14,15", Rules::default()).unwrap();
+    let mut comp = Computer::new(4, White, 1.);
+
+    let mut moves = comp.available_turns(&brd);
+    let best = comp.score_moves(&brd, &mut moves, 4)
+        .into_iter()
+        .map(|n| n.score)
+        .min();
+
+    // deepest iteration to finish is the same depth
+    comp.deadline = Some(Deadline::after(Duration::from_secs(10)));
+    let mut moves = comp.available_turns(&brd);
+    comp.score_moves(&brd, &mut moves, 3);
+    let scored = comp.score_moves(&brd, &mut moves, 4);
+
+    assert_eq!(scored.into_iter().map(|n| n.score).min(), best);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn transposition_table() {
+    let mut table = TranspositionTable::new(4);
+    assert_eq!(table.capacity(), 16);
+    assert_eq!(table.get(1), None);
+
+    let entry = Entry { hash: 1, depth: 2, score: 5, bound: Bound::Exact, best_move: None };
+    table.insert(entry);
+    assert_eq!(table.get(1), Some(&entry));
+
+    // same slot, replaces the older entry
+    let other = Entry { hash: 17, ..entry };
+    table.insert(other);
+    assert_eq!(table.get(1), None);
+    assert_eq!(table.get(17), Some(&other));
+
+    table.clear();
+    assert_eq!(table.get(17), None);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn search_reuses_positions() {
+    // kings can reach the same positions in many orders
+    let brd = Board::from_fen("W:WK21,K26:BK1,K5", Rules::default()).unwrap();
+    let mut comp = Computer::new(6, White, 1.);
+
+    let score = comp.alpha_beta(&brd, 6, isize::MIN, isize::MAX);
+    let searched = comp.last_node_count;
+    assert_eq!(score, minimax(&comp, &brd, 6));
+
+    let mut without_table = Computer::new(6, White, 1.);
+    without_table.table = TranspositionTable::new(0);
+    without_table.alpha_beta(&brd, 6, isize::MIN, isize::MAX);
+    assert!(searched < without_table.last_node_count);
+
+    // already searched to this depth
+    assert_eq!(comp.alpha_beta(&brd, 6, isize::MIN, isize::MAX), score);
+    assert_eq!(comp.last_node_count, searched + 1);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn material_evaluator() {
+    let brd = Board::from_fen("W:W21,K26:B1,5,K9", Rules::default()).unwrap();
+
+    assert_eq!(Material.evaluate(&brd), brd.score());
+    assert_eq!(WeightedEvaluator::new(Weights::material()).evaluate(&brd), brd.score() * 100);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn weighted_evaluator_symmetric() {
+    // mirrored positions are level
+    let brd = Board::init_game(Board::new(8, 8, Black), 3);
+
+    for weights in [Weights::material(), Weights::balanced(), Weights::aggressive(), Weights::defensive()] {
+        assert_eq!(WeightedEvaluator::new(weights).evaluate(&brd), 0);
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn weighted_evaluator_terms() {
+    let only = |weights: Weights| WeightedEvaluator::new(weights);
+
+    // black man 3 rows up from its back row, white man on its back row
+    let brd = Board::from_fen("B:W29:B18", Rules::default()).unwrap();
+    assert_eq!(only(Weights::new(0, 0, 1, 0, 0, 0, 0)).evaluate(&brd), 4);
+    assert_eq!(only(Weights::new(0, 0, 0, 1, 0, 0, 0)).evaluate(&brd), -1);
+    // 18 is in the middle of the board, 29 is on the edge
+    assert_eq!(only(Weights::new(0, 0, 0, 0, 1, 0, 0)).evaluate(&brd), 1);
+    // black man has 2 moves, white man 1
+    assert_eq!(only(Weights::new(0, 0, 0, 0, 0, 1, 0)).evaluate(&brd), 1);
+
+    // neither man has anything in front of it
+    let brd = Board::from_fen("B:W32:B4", Rules::default()).unwrap();
+    assert_eq!(only(Weights::new(0, 0, 0, 0, 0, 0, 1)).evaluate(&brd), 0);
+
+    // white man on 9 has a clear run, the black king doesn't count
+    let brd = Board::from_fen("B:W9:BK4", Rules::default()).unwrap();
+    assert_eq!(only(Weights::new(0, 0, 0, 0, 0, 0, 1)).evaluate(&brd), -1);
+
+    // white king on 23 is in the way of black man on 18
+    let brd = Board::from_fen("B:WK23:B18", Rules::default()).unwrap();
+    assert_eq!(only(Weights::new(0, 0, 0, 0, 0, 0, 1)).evaluate(&brd), 0);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn custom_evaluator() {
+    // black only wants a man on square 13
+    let wants_13 = |board: &Board| match board.grid_cell(board.square_idx(13).unwrap()).occupant {
+        Some(_) => 1,
+        None => 0,
+    };
+
+    let brd = Board::init_game(Board::new(8, 8, Black), 3);
+    let mut comp = Computer::with_evaluator(1, Black, 1., wants_13);
+
+    let next = comp.get_move(brd).unwrap().unwrap();
+    assert_eq!(next.grid_cell(next.square_idx(13).unwrap()), Square::pc(Black, Man));
+}
+
+/////////////////
+// PERFT
+/////////////////
+
+/// Published perft counts from the start position for each depth from 1
+fn known_perft(variant: Variant) -> &'static [usize] {
+    match variant {
+        Variant::English => &[7, 49, 302, 1469, 7361, 36768, 179740, 845931],
+        Variant::International => &[9, 81, 658, 4265, 27117, 167140],
+        Variant::Russian => &[7, 49, 302, 1469, 7482, 37986, 190146, 929905],
+        Variant::Brazilian => &[7, 49, 302, 1469, 7473, 37628, 187302, 907836],
+        Variant::Pool => &[7, 49, 302, 1469, 7482, 37986, 190146, 929902],
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn perft_start_positions() {
+    for variant in crate::board::rules::VARIANTS {
+        let board = variant_start(variant);
+        // 10x10 is slow to search with the full board
+        let depth = match variant {
+            Variant::International => 5,
+            _ => 6,
+        };
+
+        for (i, expected) in known_perft(variant).iter().take(depth).enumerate() {
+            assert_eq!(perft(&board, i + 1), *expected, "{} perft({})", variant, i + 1);
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn perft_bitboard_start_positions() {
+    for variant in crate::board::rules::VARIANTS {
+        let bitboard = BitBoard::from_board(&variant_start(variant)).unwrap();
+
+        for (i, expected) in known_perft(variant).iter().enumerate() {
+            assert_eq!(bitboard.perft(i + 1), *expected, "{} bitboard perft({})", variant, i + 1);
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn perft_depth_zero() {
+    let board = variant_start(Variant::English);
+
+    assert_eq!(perft(&board, 0), 1);
+    assert!(divide(&board, 0).is_empty());
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn perft_divide() {
+    let board = variant_start(Variant::English);
+    let split = divide(&board, 4);
+
+    assert_eq!(split.len(), 7);
+    assert_eq!(split.iter().map(|(_, nodes)| nodes).sum::<usize>(), 1469);
+
+    for (mv, nodes) in split {
+        assert_eq!(perft(&mv.apply(&board), 3), nodes);
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn perft_captures() {
+    // black man on 12 has a single move, a double jump over 16 and 23
+    let board = Board::from_fen("B:W16,23,32:B12", Rules::default()).unwrap();
+
+    assert_eq!(perft(&board, 1), 1);
+    assert_eq!(divide(&board, 1)[0].0.hops().len(), 2);
+    assert_eq!(perft(&board, 2), BitBoard::from_board(&board).unwrap().perft(2));
+}
+
+/////////////////
+// OPENING BOOK
+/////////////////
+
+const BOOK_GAMES: &str = "[Event \"One\"]\n1. 11-15 23-19 *\n\n[Event \"Two\"]\n1. 11-15 24-20 *\n\n[Event \"Three\"]\n1. 9-13 22-18 *\n";
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn book_from_pdn() {
+    let book = OpeningBook::from_pdn(BOOK_GAMES, 20).unwrap();
+    let start = variant_start(Variant::English);
+
+    // start position and the two positions after black's first moves
+    assert_eq!(book.len(), 3);
+
+    let mut moves: Vec<(usize, u32)> = book.moves(&start)
+        .into_iter()
+        .map(|(mv, weight)| (start.square_number(mv.from()).unwrap(), weight))
+        .collect();
+    moves.sort();
+    assert_eq!(moves, vec![(9, 1), (11, 2)]);
+
+    let after = book.moves(&start).into_iter().find(|(mv, _)| start.square_number(mv.from()) == Some(11)).unwrap().0.apply(&start);
+    assert_eq!(book.moves(&after).len(), 2);
+
+    // only the first turn of each game
+    let short = OpeningBook::from_pdn(BOOK_GAMES, 1).unwrap();
+    assert_eq!(short.len(), 1);
+    assert!(short.moves(&after).is_empty());
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn book_text() {
+    let book = OpeningBook::from_pdn(BOOK_GAMES, 20).unwrap();
+    let text = book.to_string();
+
+    assert_eq!(text.lines().count(), 5);
+    assert_eq!(text.parse::<OpeningBook>().unwrap(), book);
+
+    let commented = format!("# opening book\n\n{}", text.replace('\n', " # move\n"));
+    assert_eq!(commented.parse::<OpeningBook>().unwrap(), book);
+
+    assert_eq!(
+        "0123 4567\n".parse::<OpeningBook>(),
+        Err(BookError::InvalidLine { line: 1, text: "0123 4567".to_string() })
+    );
+    assert_eq!(
+        "\n0123 xyz 1\n".parse::<OpeningBook>(),
+        Err(BookError::InvalidLine { line: 2, text: "0123 xyz 1".to_string() })
+    );
+    assert!(matches!(OpeningBook::from_pdn("1. 11-19 *", 20), Err(BookError::Pdn(_))));
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn book_get_move() {
+    let start = variant_start(Variant::English);
+    let book = Arc::new(OpeningBook::from_pdn("1. 9-13 22-18 *", 20).unwrap());
+
+    let book_move = Board::from_fen("W:W21-32:B1-8,10-13", Rules::default()).unwrap();
+
+    // always random without a book, always the book move with one
+    let mut comp = Computer::new(4, Black, 0.0);
+    comp.set_book(book.clone());
+
+    for _ in 0..5 {
+        assert_eq!(comp.get_move(start.clone()).unwrap(), Some(book_move.clone()));
+        assert_eq!(comp.last_depth, 0);
+
+        assert_eq!(comp.search_for(start.clone(), Duration::from_millis(50)).unwrap(), Some(book_move.clone()));
+    }
+
+    // out of the book, searches as usual
+    let mut comp = Computer::new(2, White, 1.0);
+    comp.set_book(book);
+    let next = comp.get_move(Board::from_fen("W:W21-32:B1-9,11,12,14", Rules::default()).unwrap()).unwrap();
+    assert!(next.is_some());
+    assert_eq!(comp.last_depth, 2);
+}
+
+/// Tablebase for every English position with up to 3 pieces, small enough to generate quickly in tests and shared
+/// between them
+fn english_tablebase() -> Tablebase {
+    static TABLEBASE: OnceLock<Tablebase> = OnceLock::new();
+
+    TABLEBASE.get_or_init(|| Tablebase::generate(Rules::default(), 3).unwrap()).clone()
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn tablebase_outcomes() {
+    let tablebase = english_tablebase();
+    let probe = |fen: &str| tablebase.probe(&Board::from_fen(fen, Rules::default()).unwrap()).unwrap();
+
+    // lone kings can't force anything
+    assert_eq!(probe("B:WK1:BK32"), Probe { outcome: Outcome::Draw, distance: 0 });
+
+    // two kings beat one wherever they start
+    let win = probe("B:WK1:BK32,K29");
+    assert_eq!(win.outcome, Outcome::Win);
+    assert!(win.distance > 0);
+    assert_eq!(probe("W:WK1:BK32,K29").outcome, Outcome::Loss);
+
+    // taking the last piece
+    assert_eq!(probe("B:W14:BK18"), Probe { outcome: Outcome::Win, distance: 1 });
+
+    // no moves left
+    assert_eq!(probe("W:W8:B3,4"), Probe { outcome: Outcome::Loss, distance: 0 });
+
+    // too many pieces or other rules
+    assert_eq!(tablebase.probe(&Board::from_fen("B:WK1,K2:BK32,K29", Rules::default()).unwrap()), None);
+    assert_eq!(tablebase.probe(&Board::from_fen("B:WK1:BK32", Variant::Russian.rules()).unwrap()), None);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn tablebase_consistent() {
+    let tablebase = english_tablebase();
+    let empty = BitBoard::new(Rules::default(), Black).unwrap();
+    let squares: Vec<u64> = bits(empty.playable()).map(|bit| 1 << bit).collect();
+
+    // (white, black, kings) for each of the 3 pieces
+    let materials = [
+        [(true, false, true), (false, true, true), (false, true, true)],
+        [(true, false, false), (false, true, true), (false, true, false)],
+        [(true, false, true), (true, false, false), (false, true, false)],
+    ];
+
+    for material in materials {
+        for a in &squares {
+            for b in &squares {
+                for c in &squares {
+                    if (a | b | c).count_ones() != 3 {
+                        continue;
+                    }
+
+                    let (mut white, mut black, mut kings) = (0, 0, 0);
+                    for (square, (is_white, is_black, is_king)) in [a, b, c].into_iter().zip(material) {
+                        if is_white { white |= square; }
+                        if is_black { black |= square; }
+                        if is_king { kings |= square; }
+                    }
+
+                    // men can't be on the row they'd be crowned on
+                    if white & !kings & empty.king_row(White) != 0 || black & !kings & empty.king_row(Black) != 0 {
+                        continue;
+                    }
+
+                    for turn in [Black, White] {
+                        let bitboard = empty.with_pieces(white, black, kings, turn);
+                        let probe = tablebase.probe_bitboard(&bitboard).unwrap();
+                        let next: Vec<Probe> = bitboard.moves()
+                            .iter()
+                            .map(|mv| tablebase.probe_bitboard(&bitboard.apply(mv)).unwrap())
+                            .collect();
+
+                        let losses = next.iter().filter(|p| p.outcome == Outcome::Loss).map(|p| p.distance);
+                        let expected = if next.is_empty() {
+                            Probe { outcome: Outcome::Loss, distance: 0 }
+                        } else if let Some(fastest) = losses.min() {
+                            Probe { outcome: Outcome::Win, distance: fastest + 1 }
+                        } else if next.iter().any(|p| p.outcome == Outcome::Draw) {
+                            Probe { outcome: Outcome::Draw, distance: 0 }
+                        } else {
+                            Probe { outcome: Outcome::Loss, distance: next.iter().map(|p| p.distance).max().unwrap() + 1 }
+                        };
+
+                        assert_eq!(probe, expected, "{:?}", bitboard);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn tablebase_bytes() {
+    let tablebase = Tablebase::generate(Rules::default(), 2).unwrap();
+    let bytes = tablebase.to_bytes();
+
+    assert!(bytes.starts_with(b"DRTB"));
+    assert_eq!(Tablebase::from_bytes(&bytes), Ok(tablebase));
+
+    assert_eq!(Tablebase::from_bytes(&bytes[..bytes.len() - 1]), Err(TablebaseError::Truncated));
+    assert_eq!(Tablebase::from_bytes(&bytes[..5]), Err(TablebaseError::Truncated));
+    assert_eq!(Tablebase::from_bytes(b"not a tablebase"), Err(TablebaseError::InvalidHeader));
+
+    assert_eq!(Tablebase::generate(Rules::default(), 7), Err(TablebaseError::TooManyPieces(7)));
+    assert!(matches!(Tablebase::generate(Rules::sized(9, 8, 3), 2), Err(TablebaseError::UnsupportedRules(_))));
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn tablebase_get_move() {
+    let tablebase = Arc::new(english_tablebase());
+    let mut board = Board::from_fen("B:WK1:BK32,K29", Rules::default()).unwrap();
+    let win = tablebase.probe(&board).unwrap();
+
+    let mut black = Computer::new(1, Black, 0.0);
+    black.set_tablebase(tablebase.clone());
+    let mut white = Computer::new(1, White, 0.0);
+    white.set_tablebase(tablebase.clone());
+
+    // both sides play perfectly even though they'd otherwise move at random, so the win takes exactly as long as probed
+    for ply in 0..win.distance {
+        let comp = match board.current_turn {
+            Black => &mut black,
+            White => &mut white,
+        };
+
+        let next = comp.get_move(board.clone()).unwrap().unwrap();
+        assert_eq!(comp.last_depth, 0);
+
+        let remaining = win.distance - ply - 1;
+        let expected = if ply % 2 == 0 { Outcome::Loss } else { Outcome::Win };
+        assert_eq!(tablebase.probe(&next), Some(Probe { outcome: expected, distance: remaining }));
+
+        board = next;
+    }
+
+    assert_eq!(board.num_player(White), 0);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn tablebase_search() {
+    let tablebase = Arc::new(english_tablebase());
+
+    // either king can take the man, then 2 kings beat 1 unless the king on 24 is taken straight back
+    let board = Board::from_fen("B:W19,K28:BK15,K16", Rules::default()).unwrap();
+    assert_eq!(tablebase.probe(&board), None);
+
+    let mut comp = Computer::new(1, Black, 1.0);
+    comp.set_tablebase(tablebase.clone());
+
+    let safe = Board::from_fen("W:WK28:BK15,K23", Rules::default()).unwrap();
+    for _ in 0..5 {
+        let next = comp.get_move(board.clone()).unwrap().unwrap();
+
+        assert_eq!(next, safe);
+        assert_eq!(tablebase.probe(&next).map(|p| p.outcome), Some(Outcome::Loss));
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn seeded_moves() {
+    let start = variant_start(Variant::English);
+    let moves = |seed: u64, perfect_chance: f64| {
+        let mut comp = Computer::new(3, Black, perfect_chance);
+        comp.set_seed(seed);
+
+        (0..10).map(|_| comp.get_move(start.clone()).unwrap().unwrap()).collect::<Vec<Board>>()
+    };
+
+    // random moves and ties between the best moves
+    for chance in [0.0, 1.0] {
+        assert_eq!(moves(1, chance), moves(1, chance));
+
+        let seeds: Vec<Vec<Board>> = (0..5).map(|seed| moves(seed, chance)).collect();
+        assert!(seeds.iter().any(|m| *m != seeds[0]));
+    }
+
+    // the generator carries on between moves
+    let random = moves(1, 0.0);
+    assert!(random.iter().any(|board| *board != random[0]));
+}
+
+#[cfg(feature = "serde")]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn move_serde() {
+    let mv = Move::jumps(BrdIdx::from(1, 2), vec![BrdIdx::from(3, 4), BrdIdx::from(5, 2)]).unwrap();
+
+    let json = serde_json::to_string(&mv).unwrap();
+    assert_eq!(serde_json::from_str::<Move>(&json).unwrap(), mv);
+
+    let bytes = bincode::serialize(&mv).unwrap();
+    assert_eq!(bincode::deserialize::<Move>(&bytes).unwrap(), mv);
+}
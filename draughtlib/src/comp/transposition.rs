@@ -0,0 +1,87 @@
+//! Fixed-size table of searched positions keyed by their Zobrist hash
+
+use crate::board::BrdIdx;
+
+/// Default number of bits of the hash used to index the table, 2^16 entries
+pub const DEFAULT_TABLE_BITS: u32 = 16;
+
+/// How a stored score relates to the position's true score
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Bound {
+    /// Score is exact
+    Exact,
+    /// True score is at least the stored score, the search was cut off above the window
+    Lower,
+    /// True score is at most the stored score, no move reached the window
+    Upper,
+}
+
+/// Result of searching a position
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Entry {
+    /// Full hash of the position, positions sharing a slot are told apart by this
+    pub hash: u64,
+    /// Depth of moves searched below the position
+    pub depth: usize,
+    pub score: isize,
+    pub bound: Bound,
+    /// Source and destination of the best move found, searched first when the position comes up again
+    pub best_move: Option<(BrdIdx, BrdIdx)>,
+}
+
+/// Table of searched positions, a newer entry replaces any older one in its slot
+/// 
+/// Space for the entries isn't allocated until the first is stored
+#[derive(Clone, Debug)]
+pub struct TranspositionTable {
+    entries: Vec<Option<Entry>>,
+    bits: u32,
+}
+
+impl TranspositionTable {
+    /// Create a table with 2^`bits` entries
+    pub fn new(bits: u32) -> TranspositionTable {
+        TranspositionTable {
+            entries: Vec::new(),
+            bits,
+        }
+    }
+
+    /// Number of entries the table can hold
+    pub fn capacity(&self) -> usize {
+        1 << self.bits
+    }
+
+    /// Slot in the table for a position
+    fn slot(&self, hash: u64) -> usize {
+        (hash & ((1 << self.bits) - 1)) as usize
+    }
+
+    /// Get the entry for a position, [`None`] if it hasn't been searched or has been replaced
+    pub fn get(&self, hash: u64) -> Option<&Entry> {
+        self.entries.get(self.slot(hash))?
+            .as_ref()
+            .filter(|entry| entry.hash == hash)
+    }
+
+    /// Store the result of searching a position
+    pub fn insert(&mut self, entry: Entry) {
+        if self.entries.is_empty() {
+            self.entries = vec![None; self.capacity()];
+        }
+
+        let slot = self.slot(entry.hash);
+        self.entries[slot] = Some(entry);
+    }
+
+    /// Remove every entry
+    pub fn clear(&mut self) {
+        self.entries.iter_mut().for_each(|entry| *entry = None);
+    }
+}
+
+impl Default for TranspositionTable {
+    fn default() -> TranspositionTable {
+        TranspositionTable::new(DEFAULT_TABLE_BITS)
+    }
+}
@@ -0,0 +1,63 @@
+//! Errors from across the crate, in the browser each is thrown as a JS `Error` with its message
+//!
+//! Illegal moves also set the error's `reason` to the [`Moveable`] explaining why the move wasn't made
+
+use std::fmt::{Display};
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+
+use crate::board::BrdIdx;
+use crate::board::enums::Moveable;
+
+/// Problems with a board, a move or a saved game
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DraughtError {
+    /// Board index isn't on the board
+    OutOfBounds(BrdIdx),
+    /// 1D cell index isn't on the board
+    CellOutOfBounds(usize),
+    /// Square doesn't have a piece on it
+    EmptySquare(BrdIdx),
+    /// Square's state doesn't match its occupant or a piece is on an unplayable square
+    InconsistentSquare(BrdIdx),
+    /// Piece part-way through a multi-jump isn't on the board or isn't the current player's
+    InvalidJumpingPiece(BrdIdx),
+    /// Jump given without any landing squares
+    NoHops,
+    /// Saved game couldn't be written or read back
+    Save(String),
+    /// Move wasn't made, see [`Board::can_move`](crate::board::Board::can_move)
+    IllegalMove(Moveable),
+}
+
+impl Display for DraughtError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            DraughtError::OutOfBounds(idx) => write!(f, "Square {} is not on the board", idx),
+            DraughtError::CellOutOfBounds(idx) => write!(f, "Cell {} is not on the board", idx),
+            DraughtError::EmptySquare(idx) => write!(f, "There is no piece on square {}", idx),
+            DraughtError::InconsistentSquare(idx) => write!(f, "Square {} has an invalid state", idx),
+            DraughtError::InvalidJumpingPiece(idx) => write!(f, "Jumping piece at {} is not the current player's", idx),
+            DraughtError::NoHops => write!(f, "Jump has no landing squares"),
+            DraughtError::Save(e) => write!(f, "Invalid saved game: {}", e),
+            DraughtError::IllegalMove(reason) => write!(f, "Illegal move: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for DraughtError {}
+
+#[cfg(target_arch = "wasm32")]
+impl From<DraughtError> for JsValue {
+    fn from(e: DraughtError) -> JsValue {
+        let err = js_sys::Error::new(&e.to_string());
+
+        if let DraughtError::IllegalMove(reason) = e {
+            // setting a property on a new Error can't fail
+            let _ = js_sys::Reflect::set(&err, &"reason".into(), &(reason as u8).into());
+        }
+
+        err.into()
+    }
+}
@@ -6,33 +6,72 @@ use crate::board::Board;
 extern crate wasm_bindgen;
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
 
 // use draught_web::log;
 
-use crate::board::{Square, BrdIdx};
-use crate::board::enums::{SquareState, Moveable, Team};
+use crate::board::{Square, BrdIdx, Piece};
+use crate::board::enums::{SquareState, Strength, Moveable, Team, GameStatus, GameResult};
+use crate::board::rules::Rules;
 // use draught_web::paint::Painter;
-use crate::comp::Computer;
+use crate::comp::{Computer, Move};
+use crate::comp::eval::{Evaluator, Weights, WeightedEvaluator};
+use crate::comp::book::OpeningBook;
+use crate::comp::tablebase::Tablebase;
+use crate::error::DraughtError;
 
 use Team::*;
 use SquareState::*;
+use Strength::*;
 
 use std::fmt::{Display};
+use std::time::Duration;
+use std::sync::Arc;
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
 
 #[cfg(test)] pub mod tests;
 
 /// Root-level structure for managing the game as a collection of board states
+///
+/// With the `serde` feature the history and AI settings are serialised, the opening book, tablebase and seed are not
+/// and have to be set again after deserialising
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Game {
     current: Board,
     selected_piece: Option<BrdIdx>,
     previous_boards: Vec<Board>,
+    future_boards: Vec<Board>,
     search_depth: usize,
+    /// Milliseconds for the AI to search for, searching to `search_depth` when 0
+    search_time: f64,
     pub last_node_count: usize,
+    /// Depth of moves that the AI looked ahead on its last move
+    pub last_depth: usize,
+    /// Weights for the AI to score boards with, piece count only when [`None`]
+    weights: Option<Weights>,
+    /// Moves for the AI to play without searching
+    #[cfg_attr(feature = "serde", serde(skip))]
+    book: Option<Arc<OpeningBook>>,
+    /// Solved endgames for the AI to play perfectly from
+    #[cfg_attr(feature = "serde", serde(skip))]
+    tablebase: Option<Arc<Tablebase>>,
+    /// Seeds each of the AI's moves when set, otherwise the AI's choices aren't reproducible
+    #[cfg_attr(feature = "serde", serde(skip))]
+    rng: Option<StdRng>,
     pub perfect_chance: f64,
+    move_limit: usize,
+    /// Ply that the players agreed to draw at, taking the turn back carries on the game
+    draw_agreed: Option<usize>,
 }
 
+/// Default number of moves each player can make without a capture or a man moving before the game is drawn
+pub const DEFAULT_MOVE_LIMIT: usize = 40;
+
 impl Game {
     /// Get a read-only copy of a previous turn's board
     pub fn previous_board(&self, turn: usize) -> &Board {
@@ -43,6 +82,68 @@ impl Game {
     pub fn current_board(&self) -> &Board {
         &self.current
     }
+
+    /// Get the computer's move from the current board, searching for the set time or to the set depth
+    fn search<E: Evaluator>(&mut self, mut comp: Computer<E>) -> Result<Option<Board>, DraughtError> {
+        if let Some(book) = &self.book {
+            comp.set_book(book.clone());
+        }
+        if let Some(tablebase) = &self.tablebase {
+            comp.set_tablebase(tablebase.clone());
+        }
+        if let Some(rng) = &mut self.rng {
+            comp.set_seed(rng.gen());
+        }
+
+        let new_brd = if self.search_time > 0. {
+            comp.search_for(self.current.clone(), Duration::from_secs_f64(self.search_time / 1000.))?
+        } else {
+            comp.get_move(self.current.clone())?
+        };
+
+        self.last_node_count = comp.last_node_count;
+        self.last_depth = comp.last_depth;
+
+        Ok(new_brd)
+    }
+
+    /// Have the AI play moves from an opening book before searching
+    pub fn set_book(&mut self, book: Arc<OpeningBook>) {
+        self.book = Some(book);
+    }
+
+    /// Stop the AI playing from an opening book
+    pub fn clear_book(&mut self) {
+        self.book = None;
+    }
+
+    /// Have the AI play perfectly from positions in an endgame tablebase
+    pub fn set_tablebase(&mut self, tablebase: Arc<Tablebase>) {
+        self.tablebase = Some(tablebase);
+    }
+
+    /// Stop the AI using an endgame tablebase
+    pub fn clear_tablebase(&mut self) {
+        self.tablebase = None;
+    }
+
+    /// Iterate over every board in the game from the first to the current
+    fn boards(&self) -> impl DoubleEndedIterator<Item = &Board> {
+        self.previous_boards.iter().chain(std::iter::once(&self.current))
+    }
+
+    /// Whether the move between two consecutive boards neither captured a piece nor moved a man
+    fn is_quiet(before: &Board, after: &Board) -> bool {
+        if before.num_player(White) != after.num_player(White)
+            || before.num_player(Black) != after.num_player(Black) {
+            return false;
+        }
+
+        (0..before.num_cells()).all(|i| {
+            let is_man = |b: &Board| matches!(b.cell(i).occupant, Some(Piece { strength: Man, .. }));
+            is_man(before) == is_man(after)
+        })
+    }
 }
 
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
@@ -62,6 +163,16 @@ impl Game {
         self.current.current_turn
     }
 
+    /// Piece part-way through a multi-jump which has to keep jumping, [`None`] at the start of a turn
+    pub fn jumping_piece(&self) -> Option<BrdIdx> {
+        self.current.jumping_piece()
+    }
+
+    /// Rules being played
+    pub fn rules(&self) -> Rules {
+        self.current.rules()
+    }
+
     /// Current board's score
     pub fn score(&self) -> isize {
         self.current.score()
@@ -82,35 +193,168 @@ impl Game {
 
     /// Check if a player has won
     pub fn has_won(&self) -> Option<Team> {
+        match self.result() {
+            Some(GameResult::BlackWin) => Some(Black),
+            Some(GameResult::WhiteWin) => Some(White),
+            _ => None,
+        }
+    }
 
-        if self.current.num_player(White) == 0 {
-            Some(Black)
-        } else if self.current.num_player(Black) == 0 {
-            Some(White)
+    /// Whether the game is still being played and why it finished if not
+    /// 
+    /// A player without pieces or without a legal move on their turn has lost. A game is drawn when the same position
+    /// comes up for the third time, when neither player has captured or moved a man for the move limit or by agreement
+    pub fn status(&self) -> GameStatus {
+        if self.current.num_player(self.current.current_turn) == 0 {
+            return GameStatus::NoPieces;
+        }
+
+        // part-way through a multi-jump, the turn has to be finished first
+        if self.current.jumping_piece().is_some() {
+            return GameStatus::InProgress;
+        }
+
+        if !self.current.has_legal_move() {
+            GameStatus::NoMoves
+        } else if self.draw_agreed == Some(self.ply()) {
+            GameStatus::AgreedDraw
+        } else if self.repetitions() >= 3 {
+            GameStatus::Repetition
+        } else if self.move_limit > 0 && self.quiet_moves() >= self.move_limit * 2 {
+            GameStatus::MoveLimit
         } else {
-            None
+            GameStatus::InProgress
         }
     }
 
+    /// Outcome of the game, [`None`] while it is still being played
+    pub fn result(&self) -> Option<GameResult> {
+        match self.status() {
+            GameStatus::InProgress => None,
+            GameStatus::NoPieces | GameStatus::NoMoves => Some(GameResult::win(self.current.current_turn.opponent())),
+            GameStatus::Repetition | GameStatus::MoveLimit | GameStatus::AgreedDraw => Some(GameResult::Draw),
+        }
+    }
+
+    /// Number of turns since the last capture or man move, counting both players' turns
+    pub fn quiet_moves(&self) -> usize {
+        let boards: Vec<&Board> = self.boards().collect();
+
+        boards.windows(2)
+            .rev()
+            .take_while(|pair| Game::is_quiet(pair[0], pair[1]))
+            .count()
+    }
+
+    /// Number of times the current position has come up with the same player to move, including now
+    pub fn repetitions(&self) -> usize {
+        let hash = self.current.zobrist_hash();
+
+        // a capture or man move can't be undone, so earlier positions can't repeat
+        self.boards()
+            .rev()
+            .take(self.quiet_moves() + 1)
+            .filter(|b| b.zobrist_hash() == hash)
+            .count()
+    }
+
+    /// Number of moves each player can make without a capture or a man moving before the game is drawn, 0 for no limit
+    pub fn move_limit(&self) -> usize {
+        self.move_limit
+    }
+
+    /// Set number of moves each player can make without a capture or a man moving before the game is drawn, 0 for no limit
+    pub fn set_move_limit(&mut self, move_limit: usize) {
+        self.move_limit = move_limit;
+    }
+
+    /// Both players agree to draw the game
+    pub fn agree_draw(&mut self) {
+        self.draw_agreed = Some(self.ply());
+    }
+
     /// Get square on current board for given index
     pub fn current_cell_state(&self, idx: &BrdIdx) -> Square {
         self.current.cell(self.current.cell_idx(*idx))
     }
 
+    /// Get every legal move on the current board, see [`Board::legal_moves`]
+    pub fn legal_moves(&self) -> Vec<Move> {
+        self.current.legal_moves()
+    }
+
+    /// Get the legal moves for the piece on the given square of the current board, see [`Board::legal_moves_from`]
+    pub fn legal_moves_from(&self, idx: &BrdIdx) -> Vec<Move> {
+        self.current.legal_moves_from(*idx)
+    }
+
+    /// Get the move from the previous board to the current, [`None`] at the start of the game
+    /// 
+    /// Part-way through a multi-jump this is only the hops taken so far
+    pub fn last_move(&self) -> Option<Move> {
+        let previous = self.previous_boards.last()?;
+
+        previous.legal_moves().into_iter().find_map(|mv| {
+            if !mv.is_capture() {
+                return (mv.apply(previous) == self.current).then_some(mv);
+            }
+
+            let mut board = previous.clone();
+            let mut from = mv.from();
+
+            for (i, hop) in mv.hops().iter().enumerate() {
+                board = board.apply_jump(from, *hop);
+                from = *hop;
+
+                if board == self.current {
+                    return match i + 1 == mv.hops().len() {
+                        true => Some(mv),
+                        false => Some(Move::capture(mv.from(), mv.hops()[..=i].to_vec(), mv.captured()[..=i].to_vec(), false)),
+                    };
+                }
+            }
+
+            None
+        })
+    }
+
+    /// Tree depth for AI to search to
+    pub fn search_depth(&self) -> usize {
+        self.search_depth
+    }
+
     /// Set tree depth for AI to search to
     pub fn set_search_depth(&mut self, search_depth: usize) {
         self.search_depth = search_depth;
     }
 
-    /// Set given index as selected piece
-    /// TODO: Check whether valid square?
-    pub fn set_selected(&mut self, idx: &BrdIdx) {
-        
-        if self.current.cell(self.current.cell_idx(*idx)).state != Occupied {
-            panic!("Tried to select an unoccupied or empty square");
-        } 
+    /// Have the AI score boards with the given weights, see [`Weights`] for presets
+    pub fn set_weights(&mut self, weights: Weights) {
+        self.weights = Some(weights);
+    }
+
+    /// Have the AI score boards by piece count only, the default
+    pub fn clear_weights(&mut self) {
+        self.weights = None;
+    }
+
+    /// Set milliseconds for the AI to search for, searching as deep as it can in that time. 0 to search to a fixed depth instead
+    pub fn set_search_time(&mut self, search_time: f64) {
+        self.search_time = search_time.max(0.);
+    }
+
+    /// Set given index as selected piece, fails if the square is off the board or doesn't have a piece on it
+    pub fn set_selected(&mut self, idx: &BrdIdx) -> Result<(), DraughtError> {
+        if idx.row >= self.current.height || idx.col >= self.current.width {
+            return Err(DraughtError::OutOfBounds(*idx));
+        }
+
+        if self.current.grid_cell(*idx).state != Occupied {
+            return Err(DraughtError::EmptySquare(*idx));
+        }
 
         self.selected_piece = Some(*idx);
+        Ok(())
     }
 
     /// Set proportion of perfect moves from AI
@@ -118,31 +362,46 @@ impl Game {
         self.perfect_chance = new_chance;
     }
 
+    /// Seed the AI's random choices, the same seed gives the same moves from the same positions
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = Some(StdRng::seed_from_u64(seed));
+    }
+
+    /// Stop seeding the AI, its choices aren't reproducible
+    pub fn clear_seed(&mut self) {
+        self.rng = None;
+    }
+
     /// Clear currently selected piece
     pub fn clear_selected(&mut self) {
         self.selected_piece = None;
     }
 
-    /// Attempt to make a move given a source and destination index
-    pub fn make_move(&mut self, from: BrdIdx, to: BrdIdx) -> Moveable {
+    /// Attempt to make a move given a source and destination index, no moves are made once the game has a result
+    /// 
+    /// A move that isn't made is a [`DraughtError::IllegalMove`] with the reason from [`Board::can_move`]
+    pub fn make_move(&mut self, from: BrdIdx, to: BrdIdx) -> Result<(), DraughtError> {
+        if self.result().is_some() {
+            return Err(DraughtError::IllegalMove(Moveable::GameOver));
+        }
+
         let able = self.current.can_move(from, to);
 
         if let Moveable::Allowed = able {
-            let (_, col_diff) = Board::idx_diffs(from, to);
-            // MOVE
-            if col_diff.abs() == 1 {
-                self.execute_move(from, to);
-            } 
             // JUMP
-            else {
+            if self.current.is_jump(from, to) {
                 self.execute_jump(from, to);
+            } 
+            // MOVE
+            else {
+                self.execute_move(from, to);
             }
 
+            Ok(())
         } else {
             // log!("Unable to make move, {:?}", able);
+            Err(DraughtError::IllegalMove(able))
         }
-
-        able
     }
 
     /// Update board state with given move and push new board into current state
@@ -152,17 +411,97 @@ impl Game {
     }
 
     /// Update board state with given jump move and push new board into current state
+    /// 
+    /// Later hops of a multi-jump replace the current board instead, so that each previous board is the start of a turn
     pub fn execute_jump(&mut self, from: BrdIdx, to: BrdIdx) {
-        // set new board to current and push current to stack
-        self.push_new_board(self.current.apply_jump(from, to));
+        let new_board = self.current.apply_jump(from, to);
+
+        if self.current.jumping_piece().is_some() {
+            self.set_current(new_board);
+        } else {
+            // set new board to current and push current to stack
+            self.push_new_board(new_board);
+        }
     }
 
     /// Push current board into the previous turns and set given board to current
+    /// 
+    /// Any undone turns are dropped, the game carries on from here instead
     pub fn push_new_board(&mut self, board: Board) {
+        // a draw agreed in the turns being dropped no longer stands
+        if self.draw_agreed.is_some_and(|ply| ply > self.ply()) {
+            self.draw_agreed = None;
+        }
+
         self.previous_boards.push(self.current.clone());
+        self.future_boards.clear();
         self.set_current(board);
     }
 
+    /// Number of turns played to reach the current board
+    pub fn ply(&self) -> usize {
+        self.previous_boards.len()
+    }
+
+    /// Number of turns in the game's history including any that have been undone
+    pub fn num_plies(&self) -> usize {
+        self.previous_boards.len() + self.future_boards.len()
+    }
+
+    /// Whether there is a previous turn to go back to
+    pub fn can_undo(&self) -> bool {
+        !self.previous_boards.is_empty()
+    }
+
+    /// Whether there is an undone turn to go forward to
+    pub fn can_redo(&self) -> bool {
+        !self.future_boards.is_empty()
+    }
+
+    /// Take back the last turn, returns false if there are no turns to take back
+    /// 
+    /// Part-way through a multi-jump, this goes back to the start of the turn
+    pub fn undo(&mut self) -> bool {
+        match self.previous_boards.pop() {
+            Some(board) => {
+                let undone = std::mem::replace(&mut self.current, board);
+                self.future_boards.push(undone);
+                self.clear_selected();
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Replay the last undone turn, returns false if there are no turns to replay
+    pub fn redo(&mut self) -> bool {
+        match self.future_boards.pop() {
+            Some(board) => {
+                let redone = std::mem::replace(&mut self.current, board);
+                self.previous_boards.push(redone);
+                self.clear_selected();
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Undo or redo turns until the given number of turns have been played, returns false if there aren't that many turns
+    pub fn jump_to_ply(&mut self, ply: usize) -> bool {
+        if ply > self.num_plies() {
+            return false;
+        }
+
+        while self.ply() > ply {
+            self.undo();
+        }
+        while self.ply() < ply {
+            self.redo();
+        }
+
+        true
+    }
+
     /// Set current board to given
     pub fn set_current(&mut self, board: Board) {
         self.current = board;
@@ -171,38 +510,74 @@ impl Game {
     /// Get new game without board renderer
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen(constructor))]
     pub fn new(width: usize, height: usize, piece_rows: usize, first_turn: Team, search_depth: usize) -> Game {
+        Game::with_rules(Rules::sized(width, height, piece_rows), first_turn, search_depth)
+    }
+
+    /// Get new game played by the given rules, see [`Rules::from_variant`] for standard variants
+    pub fn with_rules(rules: Rules, first_turn: Team, search_depth: usize) -> Game {
         Game {
             current: Board::init_game(
-                Board::new(width, height, first_turn), piece_rows,
+                Board::with_rules(rules, first_turn), rules.piece_rows,
             ),
             selected_piece: None,
             previous_boards: Vec::with_capacity(10),
+            future_boards: Vec::new(),
             search_depth,
+            search_time: 0.,
             last_node_count: 0,
+            last_depth: 0,
+            weights: None,
+            book: None,
+            tablebase: None,
+            rng: None,
             perfect_chance: 0.5,
+            move_limit: DEFAULT_MOVE_LIMIT,
+            draw_agreed: None,
         }
     }
 
     /// Create computer, get move from current board and update current board
-    pub fn ai_move(&mut self) {
-        
-        let mut comp = Computer::new(self.search_depth, self.current.current_turn, self.perfect_chance);
+    /// 
+    /// Does nothing once the game has finished, fails if the current board's squares are inconsistent
+    pub fn ai_move(&mut self) -> Result<(), DraughtError> {
+        self.current.check()?;
 
-        let new_brd = comp.get_move(self.current.clone());
+        if self.status() != GameStatus::InProgress {
+            return Ok(());
+        }
 
-        self.last_node_count = comp.last_node_count;
+        let new_brd = match self.weights {
+            Some(weights) => self.search(Computer::with_evaluator(
+                self.search_depth, self.current.current_turn, self.perfect_chance, WeightedEvaluator::new(weights)
+            )),
+            None => self.search(Computer::new(self.search_depth, self.current.current_turn, self.perfect_chance)),
+        }?;
 
-        match new_brd {
-            Some(brd) => self.push_new_board(brd),
-            None => {
-                // log!("No possible moves, re-pushing current board");
+        if let Some(brd) = new_brd {
+            self.push_new_board(brd);
+        }
 
-                let mut new_brd = self.current.clone();
-                new_brd.current_turn = new_brd.current_turn.opponent();
+        Ok(())
+    }
+}
 
-                self.push_new_board(new_brd);
-            },
+#[cfg(feature = "serde")]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+impl Game {
+    /// Write the game as JSON with its history and AI settings, read back with [`Game::from_json`]
+    pub fn to_json(&self) -> Result<String, DraughtError> {
+        serde_json::to_string(self).map_err(|e| DraughtError::Save(e.to_string()))
+    }
+
+    /// Load a game written by [`Game::to_json`], fails if the JSON isn't a game or any of its boards are inconsistent
+    pub fn from_json(json: &str) -> Result<Game, DraughtError> {
+        let game: Game = serde_json::from_str(json).map_err(|e| DraughtError::Save(e.to_string()))?;
+
+        for board in game.boards().chain(game.future_boards.iter()) {
+            board.check()?;
         }
+
+        Ok(game)
     }
 }
 
@@ -7,7 +7,7 @@ use wasm_bindgen_test::*;
 wasm_bindgen_test_configure!(run_in_browser);
 
 // use crate::board::{Piece};
-use crate::board::enums::Strength::*;
+use crate::board::rules::Variant;
 // use crate::board::enums::Team::*;
 
 
@@ -21,7 +21,7 @@ fn make_move() {
     let from = BrdIdx::from(5, 2);
     let to = BrdIdx::from(4, 1);
 
-    game.make_move(from, to);
+    game.make_move(from, to).unwrap();
     let board = game.current_board();
 
     assert_eq!(board.cell(board.cell_index(4, 1)), Square::pc(Black, Man));
@@ -31,7 +31,7 @@ fn make_move() {
     let from = BrdIdx::from(2, 1);
     let to = BrdIdx::from(3, 2);
 
-    game.make_move(from, to);
+    game.make_move(from, to).unwrap();
     let board = game.current_board();
     
     assert_eq!(board.cell(board.cell_index(3, 2)), Square::pc(White, Man));
@@ -51,14 +51,565 @@ fn make_jump() {
     game.current.set_cell(
         game.current.cell_idx(BrdIdx::from(4, 1)), 
         square
-    );
+    ).unwrap();
 
     let from = BrdIdx::from(5, 2);
     let to = BrdIdx::from(3, 0);
 
-    game.make_move(from, to);
+    game.make_move(from, to).unwrap();
 
     // log!("{}", game);
     // log!("{}", game.previous_board(0));
 }
 
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn make_move_capture_required() {
+    let mut game = Game::new(8, 8, 3, Black, 3);
+
+    let square = Square::pc(White, Man);
+    game.current.set_cell(
+        game.current.cell_idx(BrdIdx::from(4, 1)), 
+        square
+    ).unwrap();
+
+    // black has to take the white piece
+    let from = BrdIdx::from(5, 6);
+    let to = BrdIdx::from(4, 7);
+
+    assert_eq!(game.make_move(from, to), Err(DraughtError::IllegalMove(Moveable::CaptureRequired)));
+    assert_eq!(game.current_turn(), Black);
+
+    let from = BrdIdx::from(5, 2);
+    let to = BrdIdx::from(3, 0);
+
+    assert_eq!(game.make_move(from, to), Ok(()));
+    assert_eq!(game.current_turn(), White);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn make_multi_jump() {
+    let mut game = Game::new(8, 8, 3, White, 3);
+
+    let mut board = Board::new(8, 8, White);
+    board.set_cell(board.cell_index(1, 2), Square::pc(White, Man)).unwrap();
+    board.set_cell(board.cell_index(2, 3), Square::pc(Black, Man)).unwrap();
+    board.set_cell(board.cell_index(4, 5), Square::pc(Black, Man)).unwrap();
+    board.set_cell(board.cell_index(7, 0), Square::pc(Black, Man)).unwrap();
+    game.set_current(board);
+
+    assert_eq!(game.make_move(BrdIdx::from(1, 2), BrdIdx::from(3, 4)), Ok(()));
+
+    // same player carries on
+    assert_eq!(game.current_turn(), White);
+    assert_eq!(game.jumping_piece(), Some(BrdIdx::from(3, 4)));
+
+    assert_eq!(game.make_move(BrdIdx::from(3, 4), BrdIdx::from(5, 6)), Ok(()));
+
+    assert_eq!(game.current_turn(), Black);
+    assert_eq!(game.jumping_piece(), None);
+    assert_eq!(game.current_board().num_player(Black), 1);
+    // whole turn is one previous board
+    assert_eq!(game.previous_boards.len(), 1);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn last_move() {
+    let mut game = Game::new(8, 8, 3, Black, 3);
+    assert_eq!(game.last_move(), None);
+
+    game.make_move(BrdIdx::from(5, 0), BrdIdx::from(4, 1)).unwrap();
+    let mv = game.last_move().unwrap();
+    assert_eq!((mv.from(), mv.to(), mv.is_capture()), (BrdIdx::from(5, 0), BrdIdx::from(4, 1), false));
+
+    let mut game = Game::new(8, 8, 3, White, 3);
+
+    let mut board = Board::new(8, 8, White);
+    board.set_cell(board.cell_index(1, 2), Square::pc(White, Man)).unwrap();
+    board.set_cell(board.cell_index(2, 3), Square::pc(Black, Man)).unwrap();
+    board.set_cell(board.cell_index(4, 5), Square::pc(Black, Man)).unwrap();
+    board.set_cell(board.cell_index(7, 0), Square::pc(Black, Man)).unwrap();
+    game.set_current(board);
+
+    // part way through, only the first hop
+    game.make_move(BrdIdx::from(1, 2), BrdIdx::from(3, 4)).unwrap();
+    let mv = game.last_move().unwrap();
+    assert_eq!(mv.hops(), &[BrdIdx::from(3, 4)]);
+    assert_eq!(mv.captured(), vec![BrdIdx::from(2, 3)]);
+
+    game.make_move(BrdIdx::from(3, 4), BrdIdx::from(5, 6)).unwrap();
+    let mv = game.last_move().unwrap();
+    assert_eq!(mv.from(), BrdIdx::from(1, 2));
+    assert_eq!(mv.hops(), &[BrdIdx::from(3, 4), BrdIdx::from(5, 6)]);
+    assert_eq!(mv.captured(), vec![BrdIdx::from(2, 3), BrdIdx::from(4, 5)]);
+
+    game.undo();
+    assert_eq!(game.last_move(), None);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn international_game() {
+    let game = Game::with_rules(Rules::from_variant(Variant::International), White, 3);
+    let board = game.current_board();
+
+    assert_eq!(board.width, 10);
+    assert_eq!(board.height, 10);
+    assert_eq!(board.num_player(White), 20);
+    assert_eq!(board.num_player(Black), 20);
+    assert_eq!(game.rules().capture_rule, crate::board::rules::CaptureRule::Majority);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn make_flying_king_jump() {
+    let mut game = Game::with_rules(Variant::Russian.rules(), White, 3);
+
+    let mut board = Board::with_rules(Variant::Russian.rules(), White);
+    board.set_cell(board.cell_index(2, 1), Square::pc(White, King)).unwrap();
+    board.set_cell(board.cell_index(4, 3), Square::pc(Black, Man)).unwrap();
+    board.set_cell(board.cell_index(5, 6), Square::pc(Black, Man)).unwrap();
+    board.set_cell(board.cell_index(7, 0), Square::pc(Black, Man)).unwrap();
+    game.set_current(board);
+
+    assert_eq!(game.make_move(BrdIdx::from(2, 1), BrdIdx::from(6, 5)), Ok(()));
+    assert_eq!(game.jumping_piece(), Some(BrdIdx::from(6, 5)));
+
+    assert_eq!(game.make_move(BrdIdx::from(6, 5), BrdIdx::from(4, 7)), Ok(()));
+    assert_eq!(game.current_turn(), Black);
+    assert_eq!(game.current_board().num_player(Black), 1);
+}
+
+/// Game on an empty 8x8 board with the given pieces placed
+fn game_with_pieces(pieces: &[(usize, usize, Team, Strength)], turn: Team) -> Game {
+    let mut game = Game::new(8, 8, 3, turn, 3);
+    let mut board = Board::new(8, 8, turn);
+
+    for (row, col, team, strength) in pieces {
+        let idx = board.cell_index(*row, *col);
+        board.set_cell(idx, Square::pc(*team, *strength)).unwrap();
+    }
+
+    game.set_current(board);
+    game
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn new_game_in_progress() {
+    let game = Game::new(8, 8, 3, Black, 3);
+
+    assert_eq!(game.status(), GameStatus::InProgress);
+    assert_eq!(game.result(), None);
+    assert_eq!(game.has_won(), None);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn no_pieces_loss() {
+    let game = game_with_pieces(&[(0, 1, White, Man)], Black);
+
+    assert_eq!(game.status(), GameStatus::NoPieces);
+    assert_eq!(game.result(), Some(GameResult::WhiteWin));
+    assert_eq!(game.has_won(), Some(White));
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn no_moves_loss() {
+    // black man in the corner is blocked and can't jump off the board
+    let mut game = game_with_pieces(&[(1, 0, Black, Man), (0, 1, White, Man)], Black);
+
+    assert_eq!(game.status(), GameStatus::NoMoves);
+    assert_eq!(game.result(), Some(GameResult::WhiteWin));
+    assert_eq!(game.has_won(), Some(White));
+
+    // computer can't move either, nothing gets pushed
+    game.ai_move().unwrap();
+    assert_eq!(game.previous_boards.len(), 0);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn threefold_repetition() {
+    let mut game = game_with_pieces(&[(7, 6, Black, King), (0, 1, White, King)], Black);
+    game.set_move_limit(0);
+
+    for _ in 0..2 {
+        assert_eq!(game.status(), GameStatus::InProgress);
+
+        game.make_move(BrdIdx::from(7, 6), BrdIdx::from(6, 7)).unwrap();
+        game.make_move(BrdIdx::from(0, 1), BrdIdx::from(1, 0)).unwrap();
+        game.make_move(BrdIdx::from(6, 7), BrdIdx::from(7, 6)).unwrap();
+        game.make_move(BrdIdx::from(1, 0), BrdIdx::from(0, 1)).unwrap();
+    }
+
+    assert_eq!(game.repetitions(), 3);
+    assert_eq!(game.status(), GameStatus::Repetition);
+    assert_eq!(game.result(), Some(GameResult::Draw));
+    assert_eq!(game.has_won(), None);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn move_limit_draw() {
+    let mut game = game_with_pieces(&[(7, 6, Black, King), (0, 1, White, King), (0, 7, White, Man)], Black);
+    game.set_move_limit(2);
+
+    game.make_move(BrdIdx::from(7, 6), BrdIdx::from(6, 7)).unwrap();
+    game.make_move(BrdIdx::from(0, 1), BrdIdx::from(1, 0)).unwrap();
+    game.make_move(BrdIdx::from(6, 7), BrdIdx::from(5, 6)).unwrap();
+    assert_eq!(game.quiet_moves(), 3);
+    assert_eq!(game.status(), GameStatus::InProgress);
+
+    // man moving resets the count
+    game.make_move(BrdIdx::from(0, 7), BrdIdx::from(1, 6)).unwrap();
+    assert_eq!(game.quiet_moves(), 0);
+
+    game.make_move(BrdIdx::from(5, 6), BrdIdx::from(4, 7)).unwrap();
+    game.make_move(BrdIdx::from(1, 0), BrdIdx::from(2, 1)).unwrap();
+    game.make_move(BrdIdx::from(4, 7), BrdIdx::from(5, 6)).unwrap();
+    assert_eq!(game.status(), GameStatus::InProgress);
+
+    game.make_move(BrdIdx::from(2, 1), BrdIdx::from(1, 0)).unwrap();
+    assert_eq!(game.quiet_moves(), 4);
+    assert_eq!(game.status(), GameStatus::MoveLimit);
+    assert_eq!(game.result(), Some(GameResult::Draw));
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn agreed_draw() {
+    let mut game = Game::new(8, 8, 3, Black, 3);
+    game.agree_draw();
+
+    assert_eq!(game.status(), GameStatus::AgreedDraw);
+    assert_eq!(game.result(), Some(GameResult::Draw));
+
+    // finished games aren't played on
+    game.ai_move().unwrap();
+    assert_eq!(game.previous_boards.len(), 0);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn make_move_game_over() {
+    let mut game = Game::new(8, 8, 3, Black, 3);
+    let from = BrdIdx::from(5, 0);
+    let to = BrdIdx::from(4, 1);
+    assert_eq!(game.current_board().can_move(from, to), Moveable::Allowed);
+
+    game.agree_draw();
+
+    // the move would be legal but the game has finished
+    assert_eq!(game.make_move(from, to), Err(DraughtError::IllegalMove(Moveable::GameOver)));
+    assert_eq!(game.ply(), 0);
+    assert_eq!(game.result(), Some(GameResult::Draw));
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn agreed_draw_undo() {
+    let mut game = Game::new(8, 8, 3, Black, 3);
+    game.make_move(BrdIdx::from(5, 0), BrdIdx::from(4, 1)).unwrap();
+    game.agree_draw();
+    assert_eq!(game.status(), GameStatus::AgreedDraw);
+
+    // taking the move back carries on the game
+    assert!(game.undo());
+    assert_eq!(game.status(), GameStatus::InProgress);
+    game.ai_move().unwrap();
+    assert_eq!(game.ply(), 1);
+    assert_eq!(game.status(), GameStatus::InProgress);
+
+    // the draw is back when replaying the same turns
+    let mut game = Game::new(8, 8, 3, Black, 3);
+    game.make_move(BrdIdx::from(5, 0), BrdIdx::from(4, 1)).unwrap();
+    game.agree_draw();
+    game.undo();
+    game.redo();
+    assert_eq!(game.status(), GameStatus::AgreedDraw);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn undo_redo() {
+    let mut game = Game::new(8, 8, 3, Black, 3);
+    let start = game.current_board().clone();
+
+    assert!(!game.can_undo());
+    assert!(!game.undo());
+
+    game.make_move(BrdIdx::from(5, 2), BrdIdx::from(4, 1)).unwrap();
+    let first = game.current_board().clone();
+    game.make_move(BrdIdx::from(2, 1), BrdIdx::from(3, 2)).unwrap();
+    let second = game.current_board().clone();
+
+    assert_eq!(game.ply(), 2);
+
+    assert!(game.undo());
+    assert_eq!(*game.current_board(), first);
+    assert_eq!(game.current_turn(), White);
+    assert!(game.undo());
+    assert_eq!(*game.current_board(), start);
+    assert_eq!(game.ply(), 0);
+    assert_eq!(game.num_plies(), 2);
+
+    assert!(game.can_redo());
+    assert!(game.redo());
+    assert!(game.redo());
+    assert!(!game.redo());
+    assert_eq!(*game.current_board(), second);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn undo_truncates_history() {
+    let mut game = Game::new(8, 8, 3, Black, 3);
+
+    game.make_move(BrdIdx::from(5, 2), BrdIdx::from(4, 1)).unwrap();
+    game.make_move(BrdIdx::from(2, 1), BrdIdx::from(3, 2)).unwrap();
+    game.undo();
+
+    // different move after undoing drops the old line
+    game.make_move(BrdIdx::from(2, 3), BrdIdx::from(3, 4)).unwrap();
+
+    assert!(!game.can_redo());
+    assert_eq!(game.num_plies(), 2);
+    assert_eq!(game.current_board().cell(game.current_board().cell_index(3, 4)), Square::pc(White, Man));
+    assert_eq!(game.current_board().cell(game.current_board().cell_index(3, 2)), Square::empty());
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn jump_to_ply() {
+    let mut game = Game::new(8, 8, 3, Black, 3);
+    let start = game.current_board().clone();
+
+    game.make_move(BrdIdx::from(5, 2), BrdIdx::from(4, 1)).unwrap();
+    let first = game.current_board().clone();
+    game.make_move(BrdIdx::from(2, 1), BrdIdx::from(3, 2)).unwrap();
+    let second = game.current_board().clone();
+
+    assert!(game.jump_to_ply(0));
+    assert_eq!(*game.current_board(), start);
+    assert!(game.jump_to_ply(2));
+    assert_eq!(*game.current_board(), second);
+    assert!(game.jump_to_ply(1));
+    assert_eq!(*game.current_board(), first);
+
+    assert!(!game.jump_to_ply(3));
+    assert_eq!(game.ply(), 1);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn undo_mid_jump() {
+    let mut game = game_with_pieces(&[(7, 0, Black, Man), (6, 1, White, Man), (4, 3, White, Man), (0, 7, White, Man)], Black);
+    let start = game.current_board().clone();
+
+    game.make_move(BrdIdx::from(7, 0), BrdIdx::from(5, 2)).unwrap();
+    assert!(game.jumping_piece().is_some());
+
+    // back to the start of the turn
+    assert!(game.undo());
+    assert_eq!(*game.current_board(), start);
+    assert_eq!(game.jumping_piece(), None);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn ai_move_search_time() {
+    let mut game = Game::new(8, 8, 3, Black, 1);
+    game.set_perfect_chance(1.);
+    game.set_search_time(20.);
+
+    game.ai_move().unwrap();
+
+    assert_eq!(game.ply(), 1);
+    assert!(game.last_depth > 1);
+    assert!(game.last_node_count > 0);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn ai_move_weights() {
+    let mut game = Game::new(8, 8, 3, Black, 3);
+    game.set_weights(Weights::aggressive());
+
+    game.ai_move().unwrap();
+    game.ai_move().unwrap();
+
+    assert_eq!(game.ply(), 2);
+
+    game.clear_weights();
+    game.ai_move().unwrap();
+
+    assert_eq!(game.ply(), 3);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn legal_moves() {
+    let mut game = Game::new(8, 8, 3, Black, 3);
+
+    assert_eq!(game.legal_moves().len(), 7);
+    assert_eq!(game.legal_moves_from(&BrdIdx::from(5, 2)).len(), 2);
+
+    let mv = game.legal_moves_from(&BrdIdx::from(5, 2))[0].clone();
+    game.make_move(mv.from(), mv.to()).unwrap();
+
+    // white's turn now
+    assert!(game.legal_moves_from(&BrdIdx::from(4, 1)).is_empty());
+    assert!(game.legal_moves().iter().all(|m| game.current_cell_state(&m.from()).occupant.unwrap().team == White));
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn ai_move_book() {
+    let book = OpeningBook::from_pdn("1. 9-13 22-18 *", 20).unwrap();
+    let mut game = Game::new(8, 8, 3, Black, 3);
+    game.set_perfect_chance(0.0);
+    game.set_book(Arc::new(book));
+
+    game.ai_move().unwrap();
+    game.ai_move().unwrap();
+    assert_eq!(game.current_board().to_fen(), Board::from_fen("B:W21,23-32,18:B1-8,10-13", Rules::default()).unwrap().to_fen());
+    assert_eq!(game.last_depth, 0);
+
+    game.clear_book();
+    game.ai_move().unwrap();
+    assert_eq!(game.ply(), 3);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn ai_move_seeded() {
+    // half of the moves are random, the rest break ties at random
+    let play = |seed: u64| {
+        let mut game = Game::new(8, 8, 3, Black, 2);
+        game.set_perfect_chance(0.5);
+        game.set_seed(seed);
+
+        for _ in 0..30 {
+            game.ai_move().unwrap();
+        }
+
+        game.boards().cloned().collect::<Vec<Board>>()
+    };
+
+    assert_eq!(play(7), play(7));
+    assert_ne!(play(7), play(8));
+
+    // clearing the seed goes back to unseeded choices
+    let mut game = Game::new(8, 8, 3, Black, 2);
+    game.set_seed(7);
+    game.clear_seed();
+    game.ai_move().unwrap();
+    assert_eq!(game.ply(), 1);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn set_selected_errors() {
+    let mut game = Game::new(8, 8, 3, Black, 3);
+
+    assert_eq!(game.set_selected(&BrdIdx::from(8, 1)), Err(DraughtError::OutOfBounds(BrdIdx::from(8, 1))));
+    assert_eq!(game.set_selected(&BrdIdx::from(4, 1)), Err(DraughtError::EmptySquare(BrdIdx::from(4, 1))));
+    assert_eq!(game.set_selected(&BrdIdx::from(5, 0)), Ok(()));
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn ai_move_inconsistent_board() {
+    let mut game = Game::new(8, 8, 3, Black, 3);
+
+    // part-way through a double jump
+    let mut board = Board::new(8, 8, Black);
+    board.set_cell(board.cell_idx(BrdIdx::from(6, 1)), Square::pc(Black, Man)).unwrap();
+    board.set_cell(board.cell_idx(BrdIdx::from(5, 2)), Square::pc(White, Man)).unwrap();
+    board.set_cell(board.cell_idx(BrdIdx::from(3, 4)), Square::pc(White, Man)).unwrap();
+    let mut board = board.apply_jump(BrdIdx::from(6, 1), BrdIdx::from(4, 3));
+    assert_eq!(board.jumping_piece(), Some(BrdIdx::from(4, 3)));
+
+    // then the jumping piece goes missing
+    board.set_cell(board.cell_idx(BrdIdx::from(4, 3)), Square::empty()).unwrap();
+    game.set_current(board);
+
+    assert_eq!(game.ai_move(), Err(DraughtError::InvalidJumpingPiece(BrdIdx::from(4, 3))));
+    assert_eq!(game.ply(), 0);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn draught_error_text() {
+    assert_eq!(Move::jumps(BrdIdx::from(5, 0), vec![]), Err(DraughtError::NoHops));
+    assert_eq!(DraughtError::NoHops.to_string(), "Jump has no landing squares");
+    assert_eq!(DraughtError::CellOutOfBounds(64).to_string(), "Cell 64 is not on the board");
+    assert_eq!(DraughtError::IllegalMove(Moveable::CaptureRequired).to_string(), "Illegal move: A capture is available and has to be taken");
+}
+
+#[cfg(feature = "serde")]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn game_serde() {
+    let mut game = Game::with_rules(Rules::from_variant(Variant::Russian), White, 5);
+    game.set_weights(Weights::aggressive());
+    game.set_search_time(250.);
+    game.set_perfect_chance(0.75);
+    game.set_move_limit(30);
+    game.set_seed(7);
+    game.ai_move().unwrap();
+    game.ai_move().unwrap();
+    game.ai_move().unwrap();
+    game.undo();
+
+    let check = |loaded: &Game| {
+        assert_eq!(loaded.current_board(), game.current_board());
+        assert_eq!(loaded.previous_boards, game.previous_boards);
+        assert_eq!(loaded.future_boards, game.future_boards);
+        assert_eq!(loaded.rules(), game.rules());
+        assert_eq!(loaded.search_depth, 5);
+        assert_eq!(loaded.search_time, 250.);
+        assert_eq!(loaded.weights, Some(Weights::aggressive()));
+        assert_eq!(loaded.perfect_chance, 0.75);
+        assert_eq!(loaded.move_limit(), 30);
+        // the seed isn't kept
+        assert!(loaded.rng.is_none());
+    };
+
+    let json = serde_json::to_string(&game).unwrap();
+    check(&serde_json::from_str(&json).unwrap());
+
+    let bytes = bincode::serialize(&game).unwrap();
+    check(&bincode::deserialize(&bytes).unwrap());
+}
+
+#[cfg(feature = "serde")]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn game_json() {
+    let mut game = Game::new(8, 8, 3, Black, 2);
+    game.set_perfect_chance(0.3);
+    game.make_move(BrdIdx::from(5, 0), BrdIdx::from(4, 1)).unwrap();
+    game.ai_move().unwrap();
+
+    let loaded = Game::from_json(&game.to_json().unwrap()).unwrap();
+    assert_eq!(loaded.current_board(), game.current_board());
+    assert_eq!(loaded.ply(), 2);
+    assert_eq!(loaded.search_depth(), 2);
+    assert_eq!(loaded.perfect_chance, 0.3);
+
+    assert!(matches!(Game::from_json("not a game"), Err(DraughtError::Save(_))));
+
+    // a piece on an unplayable square
+    let json = Game::new(8, 8, 3, Black, 2).to_json().unwrap().replacen(
+        r#"{"occupant":null,"state":"Unplayable"}"#,
+        r#"{"occupant":{"team":"White","strength":"Man"},"state":"Occupied"}"#,
+        1,
+    );
+    assert_eq!(Game::from_json(&json).unwrap_err(), DraughtError::InconsistentSquare(BrdIdx::from(0, 0)));
+}
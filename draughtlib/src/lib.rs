@@ -1,9 +1,40 @@
+/// Print to stdout, or the browser console under WebAssembly, for the debugging features
+#[cfg(any(feature = "debug_logs", feature = "time_ex"))]
+macro_rules! log {
+    ( $( $t:tt )* ) => {
+        $crate::console_log(&format!( $( $t )* ))
+    }
+}
+
+#[cfg(all(any(feature = "debug_logs", feature = "time_ex"), target_arch = "wasm32"))]
+#[wasm_bindgen::prelude::wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = console, js_name = log)]
+    fn console_log(s: &str);
+}
+
+#[cfg(all(any(feature = "debug_logs", feature = "time_ex"), not(target_arch = "wasm32")))]
+fn console_log(s: &str) {
+    println!("{}", s);
+}
+
 pub mod board;
 pub mod game;
 pub mod comp;
+pub mod pdn;
+pub mod error;
 
 pub use board::{Board, BrdIdx, Piece, Direction, Square};
 pub use board::iter::{PieceIterator, RowIndexIterator, RowSquareIterator};
-pub use board::enums::{Team, Strength, MoveType, SquareState};
+pub use board::enums::{Team, Strength, MoveType, SquareState, Moveable, GameStatus, GameResult};
+pub use board::rules::{Rules, Variant, CaptureRule, Promotion};
+pub use board::fen::FenError;
+pub use board::bitboard::{BitBoard, BitMove, BitBoardError};
 pub use game::{Game};
-pub use comp::{Computer, Move, BoardNode};
\ No newline at end of file
+pub use comp::{Computer, Move, BoardNode};
+pub use comp::eval::{Evaluator, Material, Weights, WeightedEvaluator};
+pub use comp::perft::{perft, divide};
+pub use comp::book::{OpeningBook, BookError};
+pub use comp::tablebase::{Tablebase, Probe, Outcome, TablebaseError};
+pub use pdn::{PdnGame, PdnMove, PdnError};
+pub use error::DraughtError;
\ No newline at end of file
@@ -0,0 +1,657 @@
+//! Reading and writing games in Portable Draughts Notation (PDN)
+//!
+//! Squares are given in numeric notation, see [`Board::square_number`]. Moves are written as `11-15`, captures as `22x15`
+//! with multi-jumps listing every landing square (`9x18x27`) or only the first and last (`9x27`).
+//!
+//! Results are written from White's point of view, `1-0` (or `2-0`) being a win for White
+
+use std::fmt::{Display, Write};
+use std::str::FromStr;
+
+use crate::board::{Board, BrdIdx};
+use crate::board::fen::FenError;
+use crate::board::enums::{GameResult, MoveType, Moveable, Team};
+use crate::board::rules::{Rules, Variant};
+use crate::error::DraughtError;
+use crate::game::Game;
+
+#[cfg(test)] pub mod tests;
+
+/// Maximum length of a line of moves when writing PDN
+const LINE_LENGTH: usize = 80;
+
+/// Problems reading a PDN game or replaying it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PdnError {
+    /// Tag pair is missing its closing bracket or quote
+    UnterminatedTag,
+    /// Comment is missing its closing brace
+    UnterminatedComment,
+    /// Variation is missing its closing parenthesis
+    UnterminatedVariation,
+    /// Tag pair isn't a name followed by a quoted value
+    InvalidTag(String),
+    /// Text in the move section isn't a move number, move or result
+    InvalidToken(String),
+    /// `GameType` tag doesn't describe a supported variant
+    UnknownGameType(String),
+    /// Square number isn't on the board
+    InvalidSquare(usize),
+    /// Move can't be played from the position reached, `ply` counts from 0
+    IllegalMove { ply: usize, mv: String, reason: Moveable },
+    /// No legal move leads from one of a game's boards to the next, `ply` counts from 0
+    UnknownMove { ply: usize },
+    /// Game's rules aren't played by any variant so can't be described by a `GameType` tag
+    UnsupportedRules,
+    /// `FEN` tag giving the starting position couldn't be read
+    Fen(FenError),
+}
+
+impl From<FenError> for PdnError {
+    fn from(err: FenError) -> PdnError {
+        PdnError::Fen(err)
+    }
+}
+
+impl Display for PdnError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PdnError::UnterminatedTag => write!(f, "Unterminated tag pair"),
+            PdnError::UnterminatedComment => write!(f, "Unterminated comment"),
+            PdnError::UnterminatedVariation => write!(f, "Unterminated variation"),
+            PdnError::InvalidTag(tag) => write!(f, "Invalid tag pair: {}", tag),
+            PdnError::InvalidToken(token) => write!(f, "Invalid token: {}", token),
+            PdnError::UnknownGameType(game_type) => write!(f, "Unknown game type: {}", game_type),
+            PdnError::InvalidSquare(square) => write!(f, "Square {} is not on the board", square),
+            PdnError::IllegalMove { ply, mv, reason } => write!(f, "Illegal move {} at ply {}: {}", mv, ply, reason),
+            PdnError::UnknownMove { ply } => write!(f, "No legal move found for ply {}", ply),
+            PdnError::UnsupportedRules => write!(f, "Rules are not played by any variant"),
+            PdnError::Fen(err) => write!(f, "Invalid FEN tag: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for PdnError {}
+
+/// Single turn as written in PDN
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PdnMove {
+    /// Numbers of the squares visited, the source then each landing square
+    pub squares: Vec<usize>,
+    /// Whether the move captures
+    pub capture: bool,
+    /// Comment written after the move
+    pub comment: Option<String>,
+}
+
+impl PdnMove {
+    /// Simple move between two squares
+    pub fn step(from: usize, to: usize) -> PdnMove {
+        PdnMove {
+            squares: vec![from, to],
+            capture: false,
+            comment: None,
+        }
+    }
+
+    /// Capture visiting the given squares
+    pub fn capture(squares: Vec<usize>) -> PdnMove {
+        PdnMove {
+            squares,
+            capture: true,
+            comment: None,
+        }
+    }
+
+    /// Get the PDN move for a [`crate::Move`] on the given board
+    pub fn from_move(board: &Board, mv: &crate::Move) -> Option<PdnMove> {
+        let from = board.square_number(mv.from())?;
+
+        match mv.mv_type() {
+            MoveType::Move => Some(PdnMove::step(from, board.square_number(mv.to())?)),
+            MoveType::Jump => {
+                let mut squares = vec![from];
+                for hop in mv.hops() {
+                    squares.push(board.square_number(*hop)?);
+                }
+
+                Some(PdnMove::capture(squares))
+            },
+        }
+    }
+
+    /// Parse a move in numeric notation, ignoring any trailing `!` or `?` annotations
+    fn parse(token: &str) -> Option<PdnMove> {
+        let token = token.trim_end_matches(['!', '?']);
+        let capture = token.contains('x');
+        let separator = if capture { 'x' } else { '-' };
+
+        let squares = token.split(separator)
+            .map(|square| square.parse::<usize>().ok())
+            .collect::<Option<Vec<usize>>>()?;
+
+        if squares.len() < 2 || (!capture && squares.len() > 2) {
+            return None;
+        }
+
+        Some(PdnMove {
+            squares,
+            capture,
+            comment: None,
+        })
+    }
+}
+
+impl Display for PdnMove {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let separator = if self.capture { "x" } else { "-" };
+        let squares: Vec<String> = self.squares.iter().map(|s| s.to_string()).collect();
+
+        write!(f, "{}", squares.join(separator))
+    }
+}
+
+/// Game as written in PDN, tag pairs then the moves and result
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PdnGame {
+    /// Tag pairs in the order that they're written
+    pub tags: Vec<(String, String)>,
+    /// Comment written before the first move
+    pub comment: Option<String>,
+    /// Turns in the order played
+    pub moves: Vec<PdnMove>,
+    /// Result of the game, [`None`] for unfinished games written as `*`
+    pub result: Option<GameResult>,
+}
+
+impl PdnGame {
+    /// Get the value of a tag pair
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags.iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Set the value of a tag pair, replacing any existing value
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(n, _)| n == name) {
+            Some(tag) => tag.1 = value.to_string(),
+            None => self.tags.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    /// Rules and first player described by the `GameType` tag, English draughts if there isn't one
+    ///
+    /// Tag holds the variant's code optionally followed by the first player and board size, `20,W,10,10,N2,0`
+    pub fn rules(&self) -> Result<(Rules, Team), PdnError> {
+        let game_type = match self.tag("GameType") {
+            Some(game_type) => game_type,
+            None => return Ok((Variant::English.rules(), Variant::English.first_turn())),
+        };
+        let unknown = || PdnError::UnknownGameType(game_type.to_string());
+
+        let fields: Vec<&str> = game_type.split(',').map(str::trim).collect();
+        let variant = fields[0].parse::<usize>().ok()
+            .and_then(game_type_variant)
+            .ok_or_else(unknown)?;
+
+        let first_turn = match fields.get(1) {
+            None => variant.first_turn(),
+            Some(&"W") => Team::White,
+            Some(&"B") => Team::Black,
+            Some(_) => return Err(unknown()),
+        };
+
+        let rules = match (fields.get(2), fields.get(3)) {
+            (Some(width), Some(height)) => {
+                let width = width.parse::<usize>().map_err(|_| unknown())?;
+                let height = height.parse::<usize>().map_err(|_| unknown())?;
+                if width < 2 || height < 2 {
+                    return Err(unknown());
+                }
+
+                // fill the board up to the middle two rows
+                variant.rules().resized(width, height, (height - 1) / 2)
+            },
+            _ => variant.rules(),
+        };
+
+        Ok((rules, first_turn))
+    }
+
+    /// Play the game's moves through a new [`Game`], starting from the position in the `FEN` tag if there is one
+    pub fn replay(&self, search_depth: usize) -> Result<Game, PdnError> {
+        let (rules, first_turn) = self.rules()?;
+
+        let mut game = match self.tag("FEN") {
+            Some(fen) => {
+                let board = Board::from_fen(fen, rules)?;
+                let mut game = Game::with_rules(rules, board.current_turn, search_depth);
+                game.set_current(board);
+                game
+            },
+            None => Game::with_rules(rules, first_turn, search_depth),
+        };
+
+        for (ply, mv) in self.moves.iter().enumerate() {
+            play_move(&mut game, mv, ply)?;
+        }
+
+        Ok(game)
+    }
+
+    /// Read every game from a PDN file holding a collection of games
+    ///
+    /// A game finishes at a line ending with a result or at the first tag after its moves
+    pub fn parse_all(pdn: &str) -> Result<Vec<PdnGame>, PdnError> {
+        let mut chunks = vec![String::new()];
+        let mut in_moves = false;
+
+        for line in pdn.lines() {
+            let trimmed = line.trim();
+
+            if trimmed.starts_with('[') && in_moves {
+                chunks.push(String::new());
+                in_moves = false;
+            } else if !trimmed.is_empty() && !trimmed.starts_with('[') {
+                in_moves = true;
+            }
+
+            let chunk = chunks.last_mut().expect("There's always a chunk being read");
+            chunk.push_str(line);
+            chunk.push('\n');
+
+            if in_moves && trimmed.split_whitespace().last().and_then(parse_result).is_some() {
+                chunks.push(String::new());
+                in_moves = false;
+            }
+        }
+
+        chunks
+            .iter()
+            .filter(|chunk| !chunk.trim().is_empty())
+            .map(|chunk| chunk.parse())
+            .collect()
+    }
+
+    /// Get the PDN for a game's turns up to the current board
+    ///
+    /// Moves are found by checking which legal move leads from each board to the next. Games that didn't start from the
+    /// variant's usual layout get a `FEN` tag for their first board
+    pub fn from_game(game: &Game) -> Result<PdnGame, PdnError> {
+        let mut boards: Vec<&Board> = (0..game.ply())
+            .map(|turn| game.previous_board(turn))
+            .chain(std::iter::once(game.current_board()))
+            .collect();
+
+        // leave out a turn part-way through a multi-jump
+        if game.jumping_piece().is_some() {
+            boards.pop();
+        }
+
+        let first = boards[0];
+        let rules = first.rules();
+        let variant = Variant::from_rules(&rules).ok_or(PdnError::UnsupportedRules)?;
+
+        let game_type = if variant.rules() == rules && variant.first_turn() == first.current_turn {
+            game_type_code(variant).to_string()
+        } else {
+            format!("{},{},{},{},N2,0", game_type_code(variant), first.current_turn, rules.width, rules.height)
+        };
+
+        let mut tags = vec![("GameType".to_string(), game_type)];
+
+        let start = Board::init_game(Board::with_rules(rules, first.current_turn), rules.piece_rows);
+        if *first != start {
+            tags.push(("SetUp".to_string(), "1".to_string()));
+            tags.push(("FEN".to_string(), first.to_fen()));
+        }
+
+        let mut moves = Vec::with_capacity(boards.len());
+        for (ply, pair) in boards.windows(2).enumerate() {
+            let (before, after) = (pair[0], pair[1]);
+
+            let mv = before.legal_moves()
+                .into_iter()
+                .find(|mv| mv.apply(before) == *after)
+                .and_then(|mv| PdnMove::from_move(before, &mv))
+                .ok_or(PdnError::UnknownMove { ply })?;
+
+            moves.push(mv);
+        }
+
+        let result = game.result();
+        tags.push(("Result".to_string(), result_string(result, variant).to_string()));
+
+        Ok(PdnGame {
+            tags,
+            comment: None,
+            moves,
+            result,
+        })
+    }
+}
+
+impl FromStr for PdnGame {
+    type Err = PdnError;
+
+    fn from_str(s: &str) -> Result<PdnGame, PdnError> {
+        let mut game = PdnGame::default();
+        let mut result_token = None;
+        let mut chars = s.chars().peekable();
+
+        while let Some(&c) = chars.peek() {
+            match c {
+                _ if c.is_whitespace() => { chars.next(); },
+                '[' => {
+                    chars.next();
+                    let (name, value) = parse_tag(&mut chars)?;
+                    game.tags.push((name, value));
+                },
+                '{' => {
+                    chars.next();
+                    let comment = parse_comment(&mut chars)?;
+
+                    match game.moves.last_mut() {
+                        Some(mv) => mv.comment = Some(comment),
+                        None => game.comment = Some(comment),
+                    }
+                },
+                '(' => skip_variation(&mut chars)?,
+                _ => {
+                    let mut token = String::new();
+                    while let Some(&c) = chars.peek() {
+                        if c.is_whitespace() || c == '{' || c == '[' || c == '(' {
+                            break;
+                        }
+                        token.push(c);
+                        chars.next();
+                    }
+
+                    if let Some(result) = parse_result(&token) {
+                        result_token = Some(result);
+                        continue;
+                    }
+
+                    // move numbers can be joined onto the following move, "1.11-15"
+                    let mv = match token.find('.') {
+                        Some(dot) if token[..dot].chars().all(|c| c.is_ascii_digit()) => token[dot..].trim_start_matches('.'),
+                        _ => token.as_str(),
+                    };
+
+                    if mv.is_empty() {
+                        continue;
+                    }
+
+                    match PdnMove::parse(mv) {
+                        Some(mv) => game.moves.push(mv),
+                        None => return Err(PdnError::InvalidToken(token)),
+                    }
+                },
+            }
+        }
+
+        game.result = match result_token {
+            Some(result) => result,
+            None => game.tag("Result").and_then(parse_result).flatten(),
+        };
+
+        Ok(game)
+    }
+}
+
+impl Display for PdnGame {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for (name, value) in &self.tags {
+            writeln!(f, "[{} \"{}\"]", name, value.replace('\\', "\\\\").replace('"', "\\\""))?;
+        }
+        if !self.tags.is_empty() {
+            writeln!(f)?;
+        }
+
+        let variant = self.rules().ok()
+            .and_then(|(rules, _)| Variant::from_rules(&rules))
+            .unwrap_or(Variant::English);
+
+        let mut tokens = Vec::with_capacity(self.moves.len() * 2);
+        if let Some(comment) = &self.comment {
+            tokens.push(format!("{{{}}}", comment));
+        }
+        for (ply, mv) in self.moves.iter().enumerate() {
+            let mut token = String::new();
+            if ply % 2 == 0 {
+                write!(token, "{}. ", ply / 2 + 1)?;
+            }
+            write!(token, "{}", mv)?;
+            tokens.push(token);
+
+            if let Some(comment) = &mv.comment {
+                tokens.push(format!("{{{}}}", comment));
+            }
+        }
+        tokens.push(result_string(self.result, variant).to_string());
+
+        let mut line = String::new();
+        for token in tokens {
+            if !line.is_empty() && line.len() + token.len() + 1 > LINE_LENGTH {
+                writeln!(f, "{}", line)?;
+                line.clear();
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&token);
+        }
+
+        writeln!(f, "{}", line)
+    }
+}
+
+impl Game {
+    /// Load a game from PDN by replaying its moves
+    pub fn from_pdn(pdn: &str, search_depth: usize) -> Result<Game, PdnError> {
+        pdn.parse::<PdnGame>()?.replay(search_depth)
+    }
+
+    /// Write the game up to the current board as PDN
+    pub fn to_pdn(&self) -> Result<String, PdnError> {
+        Ok(PdnGame::from_game(self)?.to_string())
+    }
+
+    /// Play a whole turn written in numeric notation, `11-15` or `22x15` with multi-jumps as in PDN
+    /// 
+    /// The game is unchanged if the move can't be played
+    pub fn play_notation(&mut self, notation: &str) -> Result<(), PdnError> {
+        let mv = PdnMove::parse(notation.trim())
+            .ok_or_else(|| PdnError::InvalidToken(notation.trim().to_string()))?;
+
+        let before = self.clone();
+        let ply = self.ply();
+
+        play_move(self, &mv, ply).inspect_err(|_| *self = before)
+    }
+
+    /// Legal moves for the player to move written in numeric notation
+    pub fn legal_notation(&self) -> Vec<String> {
+        self.legal_moves()
+            .iter()
+            .filter_map(|mv| PdnMove::from_move(self.current_board(), mv))
+            .map(|mv| mv.to_string())
+            .collect()
+    }
+}
+
+/// Play one PDN move through [`Game::make_move`], filling in the landing squares of shortened multi-jumps
+fn play_move(game: &mut Game, mv: &PdnMove, ply: usize) -> Result<(), PdnError> {
+    let board = game.current_board();
+    let squares = mv.squares.iter()
+        .map(|square| board.square_idx(*square).ok_or(PdnError::InvalidSquare(*square)))
+        .collect::<Result<Vec<BrdIdx>, PdnError>>()?;
+
+    let illegal = |reason| PdnError::IllegalMove { ply, mv: mv.to_string(), reason };
+
+    let from = squares[0];
+    let hops = if mv.capture {
+        let given = &squares[1..];
+
+        // find the sequence of hops that passes through each given square in order
+        let sequence = board.jump_sequences(from)
+            .into_iter()
+            .find(|sequence| {
+                sequence.last() == given.last() && {
+                    let mut remaining = sequence.iter();
+                    given.iter().all(|square| remaining.any(|hop| hop == square))
+                }
+            });
+
+        match sequence {
+            Some(sequence) => sequence,
+            None => {
+                let reason = match board.can_move(from, squares[1]) {
+                    Moveable::Allowed => Moveable::IllegalTrajectory,
+                    reason => reason,
+                };
+                return Err(illegal(reason));
+            },
+        }
+    } else {
+        if board.is_jump(from, squares[1]) {
+            return Err(illegal(Moveable::IllegalTrajectory));
+        }
+
+        vec![squares[1]]
+    };
+
+    let mut current = from;
+    for hop in hops {
+        // moves are only refused as illegal
+        if let Err(DraughtError::IllegalMove(reason)) = game.make_move(current, hop) {
+            return Err(illegal(reason));
+        }
+        current = hop;
+    }
+
+    // capture stopped before the piece had finished jumping
+    if game.jumping_piece().is_some() {
+        return Err(illegal(Moveable::JumpInProgress));
+    }
+
+    Ok(())
+}
+
+/// Read a tag pair's name and quoted value after the opening bracket
+fn parse_tag(chars: &mut std::iter::Peekable<std::str::Chars>) -> Result<(String, String), PdnError> {
+    let mut name = String::new();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() || c == '"' || c == ']' {
+            break;
+        }
+        name.push(c);
+        chars.next();
+    }
+
+    while chars.peek().is_some_and(|c| c.is_whitespace()) {
+        chars.next();
+    }
+
+    if name.is_empty() || chars.next() != Some('"') {
+        return Err(PdnError::InvalidTag(name));
+    }
+
+    let mut value = String::new();
+    loop {
+        match chars.next() {
+            Some('\\') => value.push(chars.next().ok_or(PdnError::UnterminatedTag)?),
+            Some('"') => break,
+            Some(c) => value.push(c),
+            None => return Err(PdnError::UnterminatedTag),
+        }
+    }
+
+    while chars.peek().is_some_and(|c| c.is_whitespace()) {
+        chars.next();
+    }
+
+    match chars.next() {
+        Some(']') => Ok((name, value)),
+        Some(_) => Err(PdnError::InvalidTag(name)),
+        None => Err(PdnError::UnterminatedTag),
+    }
+}
+
+/// Read a comment's text after the opening brace
+fn parse_comment(chars: &mut std::iter::Peekable<std::str::Chars>) -> Result<String, PdnError> {
+    let mut comment = String::new();
+    loop {
+        match chars.next() {
+            Some('}') => return Ok(comment.trim().to_string()),
+            Some(c) => comment.push(c),
+            None => return Err(PdnError::UnterminatedComment),
+        }
+    }
+}
+
+/// Skip over a variation, these aren't kept
+fn skip_variation(chars: &mut std::iter::Peekable<std::str::Chars>) -> Result<(), PdnError> {
+    let mut depth = 0;
+    loop {
+        match chars.next() {
+            Some('(') => depth += 1,
+            Some(')') => {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(());
+                }
+            },
+            Some('{') => { parse_comment(chars)?; },
+            Some(_) => {},
+            None => return Err(PdnError::UnterminatedVariation),
+        }
+    }
+}
+
+/// Parse a result token, [`Some(None)`] for an unfinished game
+fn parse_result(token: &str) -> Option<Option<GameResult>> {
+    match token {
+        "1-0" | "2-0" => Some(Some(GameResult::WhiteWin)),
+        "0-1" | "0-2" => Some(Some(GameResult::BlackWin)),
+        "1/2-1/2" | "1-1" => Some(Some(GameResult::Draw)),
+        "*" => Some(None),
+        _ => None,
+    }
+}
+
+/// Result as written for a variant, International draughts scores 2 points for a win
+fn result_string(result: Option<GameResult>, variant: Variant) -> &'static str {
+    let international = variant == Variant::International;
+
+    match result {
+        None => "*",
+        Some(GameResult::WhiteWin) => if international { "2-0" } else { "1-0" },
+        Some(GameResult::BlackWin) => if international { "0-2" } else { "0-1" },
+        Some(GameResult::Draw) => if international { "1-1" } else { "1/2-1/2" },
+    }
+}
+
+/// Code for a variant in the `GameType` tag
+fn game_type_code(variant: Variant) -> usize {
+    match variant {
+        Variant::International => 20,
+        Variant::English => 21,
+        Variant::Pool => 23,
+        Variant::Russian => 25,
+        Variant::Brazilian => 26,
+    }
+}
+
+/// Variant for a code in the `GameType` tag
+fn game_type_variant(code: usize) -> Option<Variant> {
+    match code {
+        20 => Some(Variant::International),
+        21 => Some(Variant::English),
+        23 => Some(Variant::Pool),
+        25 => Some(Variant::Russian),
+        26 => Some(Variant::Brazilian),
+        _ => None,
+    }
+}
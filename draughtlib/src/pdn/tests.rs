@@ -0,0 +1,246 @@
+use super::*;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_test::*;
+
+use crate::board::Square;
+use crate::board::enums::Strength::*;
+use Team::*;
+
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test_configure!(run_in_browser);
+
+const SHORT_GAME: &str = r#"[Event "Club \"Championship\""]
+[Black "Alice"]
+[White "Bob"]
+[GameType "21"]
+[Result "*"]
+
+{Single corner opening} 1. 11-15 24-19 {only move} 2.15x24! 28x19 *
+"#;
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn parse_game() {
+    let pdn: PdnGame = SHORT_GAME.parse().unwrap();
+
+    assert_eq!(pdn.tag("Event"), Some("Club \"Championship\""));
+    assert_eq!(pdn.tag("Black"), Some("Alice"));
+    assert_eq!(pdn.tag("Round"), None);
+    assert_eq!(pdn.comment, Some("Single corner opening".to_string()));
+    assert_eq!(pdn.result, None);
+
+    assert_eq!(pdn.moves.len(), 4);
+    assert_eq!(pdn.moves[0], PdnMove::step(11, 15));
+    assert_eq!(pdn.moves[1].comment, Some("only move".to_string()));
+    assert_eq!(pdn.moves[2], PdnMove::capture(vec![15, 24]));
+    assert_eq!(pdn.moves[3], PdnMove::capture(vec![28, 19]));
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn parse_results() {
+    let pdn: PdnGame = "1. 11-15 1-0".parse().unwrap();
+    assert_eq!(pdn.result, Some(GameResult::WhiteWin));
+
+    let pdn: PdnGame = "1. 32-28 0-2".parse().unwrap();
+    assert_eq!(pdn.result, Some(GameResult::BlackWin));
+
+    let pdn: PdnGame = "[Result \"1/2-1/2\"]\n1. 11-15".parse().unwrap();
+    assert_eq!(pdn.result, Some(GameResult::Draw));
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn parse_errors() {
+    assert_eq!("1. 11-15 {unfinished".parse::<PdnGame>(), Err(PdnError::UnterminatedComment));
+    assert_eq!("[Event \"unfinished".parse::<PdnGame>(), Err(PdnError::UnterminatedTag));
+    assert_eq!("[Event unquoted]".parse::<PdnGame>(), Err(PdnError::InvalidTag("Event".to_string())));
+    assert_eq!("1. 11-15-19".parse::<PdnGame>(), Err(PdnError::InvalidToken("11-15-19".to_string())));
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn replay_game() {
+    let game = Game::from_pdn(SHORT_GAME, 3).unwrap();
+    let board = game.current_board();
+
+    assert_eq!(game.ply(), 4);
+    assert_eq!(game.current_turn(), Black);
+    assert_eq!(board.num_player(Black), 11);
+    assert_eq!(board.num_player(White), 11);
+    assert_eq!(board.grid_cell(board.square_idx(19).unwrap()), Square::pc(White, Man));
+    assert_eq!(board.grid_cell(board.square_idx(15).unwrap()), Square::empty());
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn replay_illegal_move() {
+    let err = Game::from_pdn("1. 11-15 24-19 2. 9-13", 3).unwrap_err();
+
+    assert_eq!(err, PdnError::IllegalMove { ply: 2, mv: "9-13".to_string(), reason: Moveable::CaptureRequired });
+    assert_eq!(Game::from_pdn("1. 11-40", 3).unwrap_err(), PdnError::InvalidSquare(40));
+    assert_eq!(Game::from_pdn("[GameType \"99\"]", 3).unwrap_err(), PdnError::UnknownGameType("99".to_string()));
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn play_shortened_multi_jump() {
+    let mut board = Board::new(8, 8, Black);
+    board.set_cell(board.cell_index(5, 0), Square::pc(Black, Man)).unwrap();
+    board.set_cell(board.cell_index(4, 1), Square::pc(White, Man)).unwrap();
+    board.set_cell(board.cell_index(2, 3), Square::pc(White, Man)).unwrap();
+    board.set_cell(board.cell_index(0, 7), Square::pc(White, Man)).unwrap();
+
+    // full path
+    let mut game = Game::new(8, 8, 3, Black, 3);
+    game.set_current(board.clone());
+    play_move(&mut game, &PdnMove::capture(vec![12, 19, 26]), 0).unwrap();
+    assert_eq!(game.current_board().num_player(White), 1);
+
+    // only the start and end
+    let mut game = Game::new(8, 8, 3, Black, 3);
+    game.set_current(board.clone());
+    play_move(&mut game, &PdnMove::capture(vec![12, 26]), 0).unwrap();
+    assert_eq!(game.current_board().num_player(White), 1);
+
+    // stopping part-way is illegal
+    let mut game = Game::new(8, 8, 3, Black, 3);
+    game.set_current(board);
+    assert!(matches!(
+        play_move(&mut game, &PdnMove::capture(vec![12, 19]), 0), 
+        Err(PdnError::IllegalMove { reason: Moveable::IllegalTrajectory, .. })
+    ));
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn write_game() {
+    let game = Game::from_pdn(SHORT_GAME, 3).unwrap();
+    let pdn = game.to_pdn().unwrap();
+
+    assert_eq!(pdn, "[GameType \"21\"]\n[Result \"*\"]\n\n1. 11-15 24-19 2. 15x24 28x19 *\n");
+
+    let mut written = PdnGame::from_game(&game).unwrap();
+    written.set_tag("Event", "Club \"Championship\"");
+    let read: PdnGame = written.to_string().parse().unwrap();
+
+    assert_eq!(read, written);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn round_trip_ai_game() {
+    for variant in [Variant::English, Variant::International, Variant::Russian] {
+        let mut game = Game::with_rules(variant.rules(), variant.first_turn(), 2);
+        for _ in 0..20 {
+            game.ai_move().unwrap();
+        }
+
+        let pdn = game.to_pdn().unwrap();
+        let replayed = Game::from_pdn(&pdn, 2).unwrap();
+
+        assert_eq!(replayed.ply(), game.ply());
+        assert_eq!(replayed.current_board(), game.current_board());
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn custom_game_type() {
+    let game = Game::new(6, 6, 2, White, 3);
+    let pdn = PdnGame::from_game(&game).unwrap();
+
+    assert_eq!(pdn.tag("GameType"), Some("21,W,6,6,N2,0"));
+
+    let (rules, first_turn) = pdn.rules().unwrap();
+    assert_eq!(rules, Rules::sized(6, 6, 2));
+    assert_eq!(first_turn, White);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn replay_from_fen() {
+    let pdn = "[SetUp \"1\"]\n[FEN \"B:W16,23,32:B12\"]\n\n1. 12x26 32-27 *";
+    let game = Game::from_pdn(pdn, 3).unwrap();
+    let board = game.current_board();
+
+    assert_eq!(board.num_player(White), 1);
+    assert_eq!(board.to_fen(), "B:W27:B26");
+
+    assert_eq!(
+        Game::from_pdn("[FEN \"B:W18:B40\"]", 3).unwrap_err(),
+        PdnError::Fen(FenError::SquareOutOfRange(40))
+    );
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn write_fen_tag() {
+    let mut game = Game::new(8, 8, 3, Black, 3);
+    game.set_current(Board::from_fen("B:W16,23,32:B12", Rules::default()).unwrap());
+    game.make_move(BrdIdx::from(5, 0), BrdIdx::from(3, 2)).unwrap();
+    game.make_move(BrdIdx::from(3, 2), BrdIdx::from(1, 4)).unwrap();
+
+    let pdn = PdnGame::from_game(&game).unwrap();
+    assert_eq!(pdn.tag("FEN"), Some("B:W16,23,32:B12"));
+    assert_eq!(pdn.moves, vec![PdnMove::capture(vec![12, 19, 26])]);
+
+    let replayed = pdn.replay(3).unwrap();
+    assert_eq!(replayed.current_board(), game.current_board());
+
+    // games from the usual layout don't need one
+    let pdn = PdnGame::from_game(&Game::new(8, 8, 3, Black, 3)).unwrap();
+    assert_eq!(pdn.tag("FEN"), None);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn parse_collection() {
+    let pdn = format!("{}\n\n[Event \"Second\"]\n1. 9-13 22-18 *\n\n1. 11-16 24-20 1-0\n1. 10-14 *\n", SHORT_GAME);
+    let games = PdnGame::parse_all(&pdn).unwrap();
+
+    assert_eq!(games.len(), 4);
+    assert_eq!(games[0], SHORT_GAME.parse::<PdnGame>().unwrap());
+    assert_eq!(games[1].tag("Event"), Some("Second"));
+    assert_eq!(games[1].moves, vec![PdnMove::step(9, 13), PdnMove::step(22, 18)]);
+    assert_eq!(games[2].result, Some(GameResult::WhiteWin));
+    assert_eq!(games[3].moves, vec![PdnMove::step(10, 14)]);
+
+    assert!(PdnGame::parse_all("").unwrap().is_empty());
+    assert_eq!(PdnGame::parse_all("1. 11-15 *\n1. 11-15 {oops *\n"), Err(PdnError::UnterminatedComment));
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn play_notation() {
+    let mut game = Game::new(8, 8, 3, Black, 3);
+
+    let moves = game.legal_notation();
+    assert_eq!(moves.len(), 7);
+    assert!(moves.contains(&"11-15".to_string()));
+
+    game.play_notation(" 11-15 ").unwrap();
+    game.play_notation("24-19").unwrap();
+    assert_eq!(game.ply(), 2);
+    assert_eq!(game.legal_notation(), vec!["15x24".to_string()]);
+
+    // game is left as it was
+    assert_eq!(
+        game.play_notation("9-13"),
+        Err(PdnError::IllegalMove { ply: 2, mv: "9-13".to_string(), reason: Moveable::CaptureRequired })
+    );
+    assert_eq!(game.play_notation("9 to 13"), Err(PdnError::InvalidToken("9 to 13".to_string())));
+    assert_eq!(game.ply(), 2);
+
+    game.play_notation("15x24").unwrap();
+    assert_eq!(game.current_board().num_player(White), 11);
+    assert_eq!(game.current_turn(), White);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn moveable_text() {
+    let err = PdnError::IllegalMove { ply: 2, mv: "9-13".to_string(), reason: Moveable::CaptureRequired };
+
+    assert_eq!(err.to_string(), "Illegal move 9-13 at ply 2: A capture is available and has to be taken");
+}